@@ -21,11 +21,8 @@ fn main() {
     hashvec.push(("Susie", "Squid"));
 
     // Access a value by key
-    match hashvec.get(&"Finn") {
-        Some(value) => {
-            assert_eq!(*value, "Human");
-        },
-        None => {}
+    if let Some(value) = hashvec.get(&"Finn") {
+        assert_eq!(*value, "Human");
     }
     
     // Access an entry by index
@@ -45,11 +42,8 @@ fn main() {
     assert_eq!(hashvec[4], ("Caesar", "Wolf"));
 
     // Mutate a value
-    match hashvec.get_mut(&"Sock") {
-        Some(value) => {
-            *value = "Guinea Pig";
-        },
-        None => {}
+    if let Some(value) = hashvec.get_mut(&"Sock") {
+        *value = "Guinea Pig";
     }
     assert_eq!(*hashvec.get(&"Sock").unwrap(), "Guinea Pig");
 
@@ -44,7 +44,7 @@
 //! 
 //! // Change an entry's key in-place
 //! hashvec.rename(&"Salad", "Caesar");
-//! assert_eq!(hashvec[4], ("Caesar", "Dog"));
+//! assert_eq!(hashvec[4], ("Caesar", "Wolf"));
 //! 
 //! // Mutate a value
 //! match hashvec.get_mut(&"Sock") {
@@ -82,34 +82,77 @@
 //! hashvec.clear();
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, TryReserveError};
 use std::collections::hash_map::DefaultHasher;
+use std::borrow::Borrow;
 use std::hash::{Hash, Hasher};
-use core::ops::Index;
+use std::cmp::{Ordering, Reverse};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use core::iter::FusedIterator;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use core::ops::{Bound, Index, Range, RangeBounds};
 
-#[derive(Debug)]
 pub struct HashVec<K: Eq + Hash, V> {
     entries: Vec<(K, V)>,
-    order: HashMap<u64, usize>
+    order: HashMap<u64, usize>,
+    grow_callback: Option<GrowCallback>,
+    max_len: Option<usize>,
+    shrink_policy: ShrinkPolicy
 }
 
+/// Determines whether a hashvec automatically releases unused capacity as entries are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShrinkPolicy {
+    /// Never shrink automatically.
+    #[default]
+    Never,
+    /// Shrink whenever removing an entry leaves the hashvec less than a quarter full, keeping room for twice the remaining entries.
+    WhenQuarterFull
+}
+
+/// A diagnostic callback which is invoked with the old and new capacity of a hashvec's entries whenever they reallocate.
+/// 
+/// The callback is required to be `Send + Sync` so that registering one doesn't stop the hashvec itself from being sent or shared between threads. To count events, use an atomic (or a mutex) rather than an `Rc<Cell<_>>`.
+pub type GrowCallback = Box<dyn FnMut(usize, usize) + Send + Sync>;
+
 impl<K: Eq + Hash, V> HashVec<K, V> {
     /// Creates a new, empty map.
     pub fn new() -> HashVec<K, V> {
         HashVec {
             entries: Vec::new(),
-            order: HashMap::new()
+            order: HashMap::new(),
+            grow_callback: None,
+            max_len: None,
+            shrink_policy: ShrinkPolicy::Never
         }
     }
 
     /// Creates a new, empty hashvec with the specified capacity.
+    /// 
+    /// Both backing structures are sized up front (the index map's [`HashMap::with_capacity()`] already accounts for its load factor), so the first `capacity` distinct keys can be inserted without either one reallocating.
     pub fn with_capacity(capacity: usize) -> HashVec<K, V> {
         HashVec {
             entries: Vec::with_capacity(capacity),
-            order: HashMap::with_capacity(capacity)
+            order: HashMap::with_capacity(capacity),
+            grow_callback: None,
+            max_len: None,
+            shrink_policy: ShrinkPolicy::Never
         }
     }
 
+    /// Creates a new, empty hashvec with the specified capacity, returning an error instead of panicking if the allocation fails.
+    /// 
+    /// As with [`HashVec::with_capacity()`], the first `capacity` distinct keys can then be inserted without reallocating.
+    pub fn try_with_capacity(capacity: usize) -> Result<HashVec<K, V>, TryReserveError> {
+        let mut new_hashvec = HashVec::new();
+        new_hashvec.entries.try_reserve(capacity)?;
+        new_hashvec.order.try_reserve(capacity)?;
+        Ok(new_hashvec)
+    }
+
     /// Creates a hashvec from a vector of key-value pairs.
     /// 
     /// Internally, this uses [`HashVec::append_vec()`], which means that redundant keys' entries will be overwritten and moved to the end of the hashvec sequentially.
@@ -119,11 +162,148 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         new_hashvec
     }
 
+    /// Creates a hashvec from the provided keys, in iteration order, with each value computed from its key by `f`.
+    /// 
+    /// Redundant keys are inserted again with last-write-wins semantics, keeping the position of the key's first occurrence (just like [`HashVec::insert()`]).
+    pub fn from_keys_with<I, F>(keys: I, mut f: F) -> HashVec<K, V>
+    where
+        I: IntoIterator<Item = K>,
+        F: FnMut(&K) -> V
+    {
+        let keys = keys.into_iter();
+        let mut new_hashvec = HashVec::with_capacity(keys.size_hint().0);
+        for k in keys {
+            let v = f(&k);
+            new_hashvec.insert(k, v);
+        }
+        new_hashvec
+    }
+
+    /// Creates a hashvec from an iterator of key-value pairs whose keys are already in ascending order with no repeats, such as data loaded from a sorted source.
+    /// 
+    /// Each key is hashed only once, with no separate duplicate check. If a key does repeat, its value is overwritten in-place, keeping the position of its first occurrence (just like [`HashVec::insert()`]).
+    pub fn from_iter_sorted<I: IntoIterator<Item = (K, V)>>(iter: I) -> HashVec<K, V> {
+        let iter = iter.into_iter();
+        let mut new_hashvec = HashVec::with_capacity(iter.size_hint().0);
+        for (k, v) in iter {
+            let key_hash = calculate_hash(&k);
+            match new_hashvec.order.get(&key_hash) {
+                // The input wasn't unique after all, so fall back to overwriting the earlier entry
+                Some(&index) => new_hashvec.entries[index].1 = v,
+                None => {
+                    // A fresh hashvec has no maximum length, so this can't fail
+                    let _ = new_hashvec.push_entry(key_hash, (k, v));
+                }
+            }
+        }
+        new_hashvec
+    }
+
+    /// Creates a hashvec by joining the provided hashvecs together in order.
+    /// 
+    /// This produces the same result as [`HashVec::append()`]ing each of them in turn, so redundant keys' entries are overwritten and moved to the end, but the index is only built once.
+    pub fn concat<I: IntoIterator<Item = HashVec<K, V>>>(maps: I) -> HashVec<K, V> {
+        let mut new_hashvec = HashVec::new();
+        new_hashvec.bulk_update(maps.into_iter().flat_map(|map| map.entries));
+        new_hashvec
+    }
+
+    /// Creates a hashvec which takes ownership of the provided entries as-is, rebuilding the index from them.
+    /// 
+    /// This is a low-level constructor: unlike [`HashVec::from_vec()`], redundant keys are not resolved, so every key in `entries` should be unique.
+    pub fn from_entries(entries: Vec<(K, V)>) -> HashVec<K, V> {
+        let mut new_hashvec = HashVec::new();
+        new_hashvec.entries = entries;
+        new_hashvec.rebuild_index();
+        new_hashvec
+    }
+
+    /// Creates a hashvec from the provided entries, rearranged so that the entry at index `i` is `entries[order[i]]`.
+    /// 
+    /// Like [`HashVec::from_entries()`], redundant keys are not resolved.
+    /// 
+    /// # Panics
+    /// Panics if `order` is not a permutation of `0..entries.len()`.
+    pub fn from_entries_ordered(entries: Vec<(K, V)>, order: &[usize]) -> HashVec<K, V> {
+        assert_eq!(order.len(), entries.len(), "order has {} positions, but there are {} entries", order.len(), entries.len());
+
+        let mut slots: Vec<Option<(K, V)>> = entries.into_iter().map(Some).collect();
+        let arranged: Vec<(K, V)> = order.iter()
+            .map(|i| {
+                slots.get_mut(*i)
+                    .and_then(|slot| slot.take())
+                    .unwrap_or_else(|| panic!("order is not a permutation: index {} is out of range or repeated", i))
+            })
+            .collect();
+
+        HashVec::from_entries(arranged)
+    }
+
+    /// Creates a hashvec directly from its internal parts, without re-indexing, as previously returned by [`HashVec::into_parts()`].
+    /// 
+    /// # Safety
+    /// The caller must ensure that `order` maps the hash of every key in `entries` to that entry's index, and contains nothing else. The hash of a key is the one produced by feeding it to a [`DefaultHasher`] created with [`DefaultHasher::new()`].
+    /// 
+    /// An inconsistent index won't cause undefined behavior by itself, but every other method assumes it holds, so lookups may return the wrong entries or panic.
+    pub unsafe fn from_parts(entries: Vec<(K, V)>, order: HashMap<u64, usize>) -> HashVec<K, V> {
+        let mut new_hashvec = HashVec::new();
+        new_hashvec.entries = entries;
+        new_hashvec.order = order;
+        new_hashvec
+    }
+
+    /// Creates a hashvec containing exactly one entry.
+    pub fn singleton(k: K, v: V) -> HashVec<K, V> {
+        let mut new_hashvec = HashVec::with_capacity(1);
+        new_hashvec.insert(k, v);
+        new_hashvec
+    }
+
     /// Returns the number of elements the hashvec can hold without reallocating.
     pub fn capacity(&self) -> usize {
         self.entries.capacity().min(self.order.capacity())
     }
 
+    /// Returns an estimate of the number of heap bytes allocated by the hashvec.
+    /// 
+    /// This is approximate: the index map's size is estimated from its capacity, and any heap memory owned by the keys and values themselves isn't included.
+    pub fn memory_usage(&self) -> usize {
+        let entries_bytes = self.entries.capacity() * std::mem::size_of::<(K, V)>();
+
+        // Each index map slot holds a key hash and an index, plus roughly one byte of control metadata
+        let order_bytes = self.order.capacity() * (std::mem::size_of::<(u64, usize)>() + 1);
+
+        entries_bytes + order_bytes
+    }
+
+    /// Registers a callback which is invoked with the old and new capacity whenever the entries vector reallocates to grow.
+    /// 
+    /// This covers every way of growing the hashvec: inserting or pushing single entries, [`HashVec::reserve()`], [`Extend`], and [`HashVec::bulk_update()`]. It is intended for diagnosing unexpected reallocation churn. When no callback is set, there is no overhead beyond a single check per growth.
+    pub fn set_grow_callback(&mut self, f: GrowCallback) {
+        self.grow_callback = Some(f);
+    }
+
+    /// Sets the maximum number of entries the hashvec may hold, or removes the limit if `None` is provided.
+    /// 
+    /// Once the hashvec is full, new keys are refused, though existing keys can always be overwritten. Setting a limit below the current length doesn't remove any entries.
+    /// 
+    /// Most methods refuse new keys quietly, leaving the hashvec unchanged: [`HashVec::insert()`], [`HashVec::push()`], [`HashVec::replace_all()`], [`HashVec::bulk_update()`] and [`HashVec::update_or_insert()`]. [`HashVec::checked_insert_at()`] and [`VacantEntry::try_insert()`] hand the refused entry back instead. The methods which have to return a reference to the new value can't refuse, so they panic: [`VacantEntry::insert()`], [`VacantEntry::insert_at()`], [`Entry::or_insert()`] and its siblings, [`HashVec::get_or_insert_default()`] and [`HashVec::get_or_try_insert_with()`].
+    pub fn set_max_len(&mut self, cap: Option<usize>) {
+        self.max_len = cap;
+    }
+
+    /// Returns `true` if the hashvec has a maximum length and holds at least that many entries.
+    pub fn is_full(&self) -> bool {
+        self.max_len.is_some_and(|max_len| self.len() >= max_len)
+    }
+
+    /// Sets whether the hashvec should automatically release unused capacity when entries are removed from it.
+    /// 
+    /// The policy applies to [`HashVec::pop()`] and the methods which remove entries by key. The default is [`ShrinkPolicy::Never`].
+    pub fn set_shrink_policy(&mut self, policy: ShrinkPolicy) {
+        self.shrink_policy = policy;
+    }
+
     /// Returns the number of elements in the hashvec.
     pub fn len(&self) -> usize {
         self.entries.len()
@@ -142,51 +322,215 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         self.order.clear();
     }
 
+    /// Replaces the entire contents of the hashvec with the provided entries, reusing the existing allocations.
+    /// 
+    /// Entries are stored in the order given. If the vector contains redundant keys, the last of them wins, but it keeps the position of the key's first occurrence (just like [`HashVec::insert()`]). Once the hashvec [`is_full()`](HashVec::is_full), any further new keys are dropped.
+    pub fn replace_all(&mut self, entries: Vec<(K, V)>) {
+        self.clear();
+        self.reserve(entries.len());
+
+        for (k, v) in entries {
+            let key_hash = calculate_hash(&k);
+            match self.order.get(&key_hash) {
+                Some(index) => {
+                    self.entries[*index] = (k, v);
+                },
+                None => {
+                    // Refused if the hashvec is full
+                    let _ = self.push_entry(key_hash, (k, v));
+                }
+            }
+        }
+    }
+
     /// Inserts an entry into the hashvec, or replaces an existing one.
+    /// 
+    /// If the key isn't in the hashvec yet and the hashvec [`is_full()`](HashVec::is_full), the entry is refused and this is a no-op.
     pub fn insert(&mut self, k: K, v: V) {
-        match self.order.get(&calculate_hash(&k)) {
+        let key_hash = calculate_hash(&k);
+        match self.order.get(&key_hash) {
             Some(index) => {
                 // If the key was already in the hashvec, update its entry in-place
                 self.entries[*index].1 = v;
             },
             None => {
-                // If the entry wasn't in the hashvec already, add it
-                self.order.insert(calculate_hash(&k), self.entries.len());
-                self.entries.push((k, v));
+                // If the entry wasn't in the hashvec already, add it (as long as there's room for it)
+                let _ = self.push_entry(key_hash, (k, v));
+            }
+        }
+    }
+
+    /// Inserts an entry into the hashvec at the provided index, shifting all following entries back, and returns the old value if the key was already in the hashvec.
+    /// 
+    /// If the key was already in the hashvec, its entry is moved to the provided index and its value replaced.
+    /// 
+    /// # Panics
+    /// Panics if `index` is greater than the length of the hashvec, if the key was already in the hashvec and `index` is not less than its length, or if the key is new but the hashvec [`is_full()`](HashVec::is_full).
+    pub fn insert_at(&mut self, index: usize, k: K, v: V) -> Option<V> {
+        let len = self.len();
+        match self.checked_insert_at(index, k, v) {
+            Ok(old_value) => old_value,
+            // A known key or an index past the end is a range error, anything else means the hashvec was full
+            Err((k, _)) if self.contains_key(&k) || index > len => {
+                panic!("index {} is out of bounds for a hashvec of length {}", index, len)
+            },
+            Err(_) => panic!("cannot insert a new key into a full hashvec")
+        }
+    }
+
+    /// Inserts an entry into the hashvec at the provided index (just like [`HashVec::insert_at()`]), returning the old value if the key was already in the hashvec.
+    /// 
+    /// If the index is out of range (or the key is new but the hashvec is full), the hashvec is left unchanged and the key and value are handed back as an error instead of panicking.
+    pub fn checked_insert_at(&mut self, index: usize, k: K, v: V) -> Result<Option<V>, (K, V)> {
+        let len = self.len();
+        let key_hash = calculate_hash(&k);
+
+        match self.order.get(&key_hash).copied() {
+            Some(current) => {
+                if index >= len {
+                    return Err((k, v));
+                }
+
+                // Replace the value in-place, then move the entry into position
+                let old_value = std::mem::replace(&mut self.entries[current].1, v);
+                self.move_index(current, index);
+                Ok(Some(old_value))
+            },
+            None => {
+                if index > len {
+                    return Err((k, v));
+                }
+
+                // Append the entry (as long as there's room for it), then move it into position
+                self.push_entry(key_hash, (k, v))?;
+                if index < len {
+                    self.move_index(len, index);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    // Appends an entry whose key isn't in the hashvec yet, tracks its index, and returns that index
+    // If the hashvec is full, nothing is changed and the entry is handed back instead
+    fn push_entry(&mut self, key_hash: u64, entry: (K, V)) -> Result<usize, (K, V)> {
+        if self.is_full() {
+            return Err(entry);
+        }
+
+        let index = self.entries.len();
+        let old_capacity = self.entries.capacity();
+
+        // If the entries are about to reallocate, grow the index map in lockstep so both allocations happen together
+        // (this only happens once the entries are actually full, so capacity reserved up front is never outgrown early)
+        if index == old_capacity {
+            self.entries.reserve(1);
+            self.order.reserve(self.entries.capacity().saturating_sub(self.order.len()));
+        }
+
+        self.order.insert(key_hash, index);
+        self.entries.push(entry);
+        self.notify_grow(old_capacity);
+
+        Ok(index)
+    }
+
+    // Notifies the grow callback (if there is one) when the entries have reallocated since they had the provided capacity
+    fn notify_grow(&mut self, old_capacity: usize) {
+        if let Some(callback) = self.grow_callback.as_mut() {
+            let new_capacity = self.entries.capacity();
+            if new_capacity != old_capacity {
+                callback(old_capacity, new_capacity);
             }
         }
     }
 
     /// Appends an entry to the back of the hashvec.
     /// 
-    /// If an entry with an identical key was already in the hashvec, it is removed before the new entry is inserted.
+    /// If an entry with an identical key was already in the hashvec, it is removed before the new entry is inserted. Otherwise, if the hashvec [`is_full()`](HashVec::is_full), the entry is refused and this is a no-op.
     /// 
     /// # Panics
     /// Panics if the new capacity either overflows `usize` or exceeds `isize::MAX` bytes.
     pub fn push(&mut self, entry: (K, V)) {
-        if self.contains_key(&entry.0) {
-            self.remove_key(&entry.0);
-        }
-
         let key_hash = calculate_hash(&entry.0);
-        self.order.insert(key_hash, self.entries.len());
-        self.entries.push(entry);
+        let index = match self.order.get(&key_hash).copied() {
+            Some(old_index) => {
+                // Overwrite the old entry, then move it to the back (always allowed, even if the hashvec is full)
+                let last = self.len() - 1;
+                self.entries[old_index] = entry;
+                self.move_index(old_index, last);
+                last
+            },
+            None => match self.push_entry(key_hash, entry) {
+                Ok(index) => index,
+                Err(_) => return
+            }
+        };
+
+        // Make sure moving the old entry didn't leave a stale copy or index behind (in constant time, since this runs on every push)
+        debug_assert_eq!(self.order.get(&key_hash), Some(&index), "pushed key is tracked at the wrong index");
+        debug_assert_eq!(self.order.len(), self.entries.len(), "pushed key left the index map out of step with the entries");
+        debug_assert_eq!(calculate_hash(&self.entries[index].0), key_hash, "pushed key isn't stored at its tracked index");
+    }
+
+    /// Appends an entry to the back of the hashvec (just like [`HashVec::push()`]), and returns the index it ended up at.
+    /// 
+    /// # Panics
+    /// Panics if the key isn't already in the hashvec but the hashvec [`is_full()`](HashVec::is_full), or if the new capacity either overflows `usize` or exceeds `isize::MAX` bytes.
+    pub fn push_indexed(&mut self, entry: (K, V)) -> usize {
+        assert!(self.contains_key(&entry.0) || !self.is_full(), "cannot push a new key onto a full hashvec");
+        self.push(entry);
+        self.len() - 1
     }
 
     /// Removes the last entry from the hashvec and returns it (or `None` if the hashvec is empty).
     pub fn pop(&mut self) -> Option<(K, V)> {
         let last_entry = self.entries.pop();
 
-        last_entry.map(|entry| {
+        last_entry.inspect(|entry| {
             let key_hash = calculate_hash(&entry.0);
 
             // Stop tracking the popped entry's key
             self.order.remove(&key_hash);
 
-            entry
+            self.apply_shrink_policy();
         })
     }
 
+    /// Removes up to `n` entries from the back of the hashvec and returns them.
+    /// 
+    /// The entries are returned in the order they were removed, so the last entry of the hashvec comes first, just as if [`HashVec::pop()`] had been called repeatedly. If the hashvec holds fewer than `n` entries, all of them are removed.
+    pub fn pop_n(&mut self, n: usize) -> Vec<(K, V)> {
+        let mut popped = Vec::with_capacity(n.min(self.len()));
+        while popped.len() < n {
+            match self.pop() {
+                Some(entry) => popped.push(entry),
+                None => break
+            }
+        }
+        popped
+    }
+
+    /// Shortens the hashvec, keeping the first `len` entries and dropping the rest.
+    /// 
+    /// If `len` is greater than or equal to the hashvec's current length, this is a no-op.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            drop(self.drain_range(len..));
+        }
+    }
+
+    /// Shortens the hashvec, keeping the first `len` entries and returning the rest in their stored order.
+    /// 
+    /// If `len` is greater than or equal to the hashvec's current length, nothing is removed and an empty vector is returned.
+    pub fn truncate_extract(&mut self, len: usize) -> Vec<(K, V)> {
+        if len >= self.len() {
+            return Vec::new();
+        }
+
+        self.drain_range(len..).collect()
+    }
+
     /// Appends all entries of `other` into `Self`, leaving `other` empty.
     /// 
     /// # Panics
@@ -200,6 +544,26 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         other.clear();
     }
 
+    /// Appends all entries of `other` into `Self`, leaving `other` empty, unless any of `other`'s keys are already in `Self`.
+    /// 
+    /// If there are conflicting keys, neither hashvec is modified and the conflicting keys are returned in `other`'s order.
+    pub fn append_strict(&mut self, other: &mut HashVec<K, V>) -> Result<(), Vec<K>>
+    where
+        K: Clone
+    {
+        let conflicts: Vec<K> = other.entries.iter()
+            .filter(|(k, _)| self.contains_key(k))
+            .map(|(k, _)| k.clone())
+            .collect();
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        self.append(other);
+        Ok(())
+    }
+
     /// Appends a vector of key-value pairs onto the hashvec.
     /// 
     /// Internally, this uses [`HashVec::push()`], which means that redundant keys' entries will be overwritten and moved to the end of the hashvec sequentially.
@@ -209,12 +573,69 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         }
     }
 
+    /// Pushes every key-value pair from the iterator onto the hashvec, producing the same result as calling [`HashVec::push()`] for each of them in turn.
+    /// 
+    /// Rather than re-indexing on every overwrite, the whole batch is applied at once and the index is rebuilt a single time at the end. This is optimized for large batches which overlap heavily with the existing keys (or with themselves).
+    pub fn bulk_update<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let batch: Vec<(K, V)> = iter.into_iter().collect();
+        let batch_hashes: Vec<u64> = batch.iter().map(|(k, _)| calculate_hash(k)).collect();
+
+        // Walk the batch forwards to decide which keys get in, just like pushing them one at a time would:
+        // overwritten keys always do, and new keys do by their first occurrence for as long as there's room
+        let mut room = self.max_len.map(|max_len| max_len.saturating_sub(self.entries.len()));
+        let mut admitted: HashSet<u64> = HashSet::with_capacity(batch.len());
+        for &key_hash in &batch_hashes {
+            if admitted.contains(&key_hash) {
+                continue;
+            }
+
+            if self.order.contains_key(&key_hash) {
+                admitted.insert(key_hash);
+            } else if room != Some(0) {
+                room = room.map(|room| room - 1);
+                admitted.insert(key_hash);
+            }
+        }
+
+        // Only the last occurrence of each admitted key survives, so walk the batch backwards to find those
+        let mut seen: HashSet<u64> = HashSet::with_capacity(admitted.len());
+        let mut appended: Vec<(K, V)> = batch.into_iter()
+            .zip(batch_hashes)
+            .rev()
+            .filter(|&(_, key_hash)| admitted.contains(&key_hash) && seen.insert(key_hash))
+            .map(|(entry, _)| entry)
+            .collect();
+        appended.reverse();
+
+        // Remove every existing entry which the batch overwrites
+        let mut overwritten = vec![false; self.entries.len()];
+        for key_hash in &admitted {
+            if let Some(&index) = self.order.get(key_hash) {
+                overwritten[index] = true;
+            }
+        }
+        let mut overwritten = overwritten.into_iter();
+        self.entries.retain(|_| !overwritten.next().unwrap());
+
+        // Grow at most once (through reserve, so the grow callback hears about it)
+        self.reserve(appended.len());
+        self.entries.extend(appended);
+
+        self.rebuild_index();
+    }
+
     /// Swaps the location of the provided keys' entries
     /// 
     /// If either one of the keys is not already in the hashvec, this is a no-op.
-    pub fn swap_keys(&mut self, key_a: &K, key_b: &K) {
-        let key_hash_a = calculate_hash(&key_a);
-        let key_hash_b = calculate_hash(&key_b);
+    /// 
+    /// The keys may be any borrowed form of the hashvec's key type, as long as its [`Hash`] matches the key type's (as required by [`Borrow`]).
+    pub fn swap_keys<Q>(&mut self, key_a: &Q, key_b: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized
+    {
+        let key_hash_a = calculate_hash(key_a);
+        let key_hash_b = calculate_hash(key_b);
         let op_valid = self.order.contains_key(&key_hash_a) && self.order.contains_key(&key_hash_b);
 
         if op_valid {
@@ -231,20 +652,58 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
 
     /// Swaps the location of the entries at the provided indices
     /// 
-    /// If either one of the indices exceeds the current length of the hashvec, this is a no-op.
+    /// If either one of the indices exceeds the current length of the hashvec, or if both indices are the same, this is a no-op.
     pub fn swap_indices(&mut self, index_a: usize, index_b: usize) {
-        if index_a.max(index_b) < self.len() {
-            let key_hash_a = calculate_hash(&self.entries[index_a].0);
-            let key_hash_b = calculate_hash(&self.entries[index_b].0);
-    
-            // Swap the tracked order
-            let old_index_a = *self.order.get(&key_hash_a).unwrap();
-            let old_index_b = *self.order.get(&key_hash_b).unwrap();
-            self.order.insert(key_hash_a, old_index_b);
-            self.order.insert(key_hash_b, old_index_a);
+        if index_a == index_b || index_a.max(index_b) >= self.len() {
+            return;
+        }
 
-            // Swap the actual entries
-            self.entries.swap(old_index_a, old_index_b);
+        let key_hash_a = calculate_hash(&self.entries[index_a].0);
+        let key_hash_b = calculate_hash(&self.entries[index_b].0);
+
+        // Swap the tracked order, using the provided indices directly rather than looking them back up
+        self.order.insert(key_hash_a, index_b);
+        self.order.insert(key_hash_b, index_a);
+
+        // Swap the actual entries
+        self.entries.swap(index_a, index_b);
+    }
+
+    /// Swaps the location of the entries at the provided indices, or returns both indices as an error if either one exceeds the current length of the hashvec.
+    pub fn checked_swap_indices(&mut self, index_a: usize, index_b: usize) -> Result<(), (usize, usize)> {
+        if index_a.max(index_b) >= self.len() {
+            return Err((index_a, index_b));
+        }
+
+        self.swap_indices(index_a, index_b);
+        Ok(())
+    }
+
+    /// Swaps the location of the entries at the provided indices, returning references to the keys which were originally at `index_a` and `index_b` respectively.
+    /// 
+    /// If either one of the indices exceeds the current length of the hashvec, this is a no-op and `None` is returned.
+    pub fn swap_indices_report(&mut self, index_a: usize, index_b: usize) -> Option<(&K, &K)> {
+        if index_a.max(index_b) >= self.len() {
+            return None;
+        }
+
+        self.swap_indices(index_a, index_b);
+        Some((&self.entries[index_b].0, &self.entries[index_a].0))
+    }
+
+    /// Rotates the hashvec's entries so that the provided key's entry becomes the first, preserving the cyclic order of all the others.
+    /// 
+    /// Returns `false` (and does nothing) if the key is not in the hashvec.
+    pub fn rotate_to_key(&mut self, k: &K) -> bool {
+        match self.index(k) {
+            Some(index) => {
+                if index > 0 {
+                    self.entries.rotate_left(index);
+                    self.rebuild_index();
+                }
+                true
+            },
+            None => false
         }
     }
 
@@ -253,6 +712,26 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         self.order.contains_key(&calculate_hash(k))
     }
 
+    /// Returns `true` if the hashvec contains an entry corresponding to the provided key at exactly the provided index.
+    pub fn contains_key_at(&self, k: &K, index: usize) -> bool {
+        self.index(k) == Some(index)
+    }
+
+    /// Returns an iterator over the hashvec's keys, for use as a set.
+    pub fn key_set(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns `true` if every key in the hashvec is also in `other`, regardless of values or order.
+    pub fn is_subset_of<W>(&self, other: &HashVec<K, W>) -> bool {
+        self.key_set().all(|k| other.contains_key(k))
+    }
+
+    /// Returns `true` if every key in `other` is also in the hashvec, regardless of values or order.
+    pub fn is_superset_of<W>(&self, other: &HashVec<K, W>) -> bool {
+        other.is_subset_of(self)
+    }
+
     /// Returns a reference to the value corresponding to the key, if it exists.
     pub fn get(&self, k: &K) -> Option<&V> {
         self.order.get(&calculate_hash(&k)).map(|index| {
@@ -260,6 +739,65 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         })
     }
 
+    /// Returns references to the keys and values of the entries at each of the provided indices, in the order the indices were given.
+    /// 
+    /// Indices which exceed the current length of the hashvec produce `None`.
+    pub fn get_indices<'a>(&'a self, indices: &[usize]) -> Vec<Option<(&'a K, &'a V)>> {
+        indices.iter()
+            .map(|i| self.entries.get(*i).map(|(k, v)| (k, v)))
+            .collect()
+    }
+
+    /// Returns references to the key and value of the first entry, or `None` if the hashvec is empty.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.entries.first().map(|(k, v)| (k, v))
+    }
+
+    /// Returns references to the key and value of the last entry, or `None` if the hashvec is empty.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|(k, v)| (k, v))
+    }
+
+    /// Returns references to the key and value of the first entry, or `None` if the hashvec is empty.
+    /// 
+    /// This is an alias for [`HashVec::first()`], matching [`BTreeMap::first_key_value()`](std::collections::BTreeMap::first_key_value).
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.first()
+    }
+
+    /// Returns references to the key and value of the last entry, or `None` if the hashvec is empty.
+    /// 
+    /// This is an alias for [`HashVec::last()`], matching [`BTreeMap::last_key_value()`](std::collections::BTreeMap::last_key_value).
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.last()
+    }
+
+    /// Returns the entries immediately before and after the provided key's entry, or `None` if the key is not in the hashvec.
+    /// 
+    /// Either neighbor is `None` if the key's entry is at that end of the hashvec.
+    #[allow(clippy::type_complexity)]
+    pub fn neighbors(&self, k: &K) -> Option<(Option<(&K, &V)>, Option<(&K, &V)>)> {
+        self.index(k).map(|index| {
+            let previous = index.checked_sub(1)
+                .and_then(|i| self.entries.get(i))
+                .map(|(k, v)| (k, v));
+            let next = self.entries.get(index + 1).map(|(k, v)| (k, v));
+            (previous, next)
+        })
+    }
+
+    /// Returns references to the first entry and to the slice of all following entries, or `None` if the hashvec is empty.
+    #[allow(clippy::type_complexity)]
+    pub fn split_first(&self) -> Option<((&K, &V), &[(K, V)])> {
+        self.entries.split_first().map(|((k, v), rest)| ((k, v), rest))
+    }
+
+    /// Returns references to the slice of all entries before the last, and to the last entry, or `None` if the hashvec is empty.
+    #[allow(clippy::type_complexity)]
+    pub fn split_last(&self) -> Option<(&[(K, V)], (&K, &V))> {
+        self.entries.split_last().map(|((k, v), rest)| (rest, (k, v)))
+    }
+
     /// Returns a mutable reference to the value corresponding to the key, if it exists.
     pub fn get_mut(&mut self, k: &K) -> Option<&mut V> {
         self.order.get(&calculate_hash(&k)).map(|index| {
@@ -267,13 +805,99 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         })
     }
 
+    /// Gets the given key's corresponding entry in the hashvec for in-place manipulation.
+    /// 
+    /// The key is hashed exactly once, here: the returned entry carries the resolved index and key hash, so chained operations like [`Entry::and_modify()`] and [`Entry::or_insert()`] never hash it again.
+    pub fn entry(&mut self, k: K) -> Entry<'_, K, V> {
+        let key_hash = calculate_hash(&k);
+
+        match self.order.get(&key_hash).copied() {
+            Some(index) => Entry::Occupied(OccupiedEntry {
+                hashvec: self,
+                index,
+                key_hash
+            }),
+            None => Entry::Vacant(VacantEntry {
+                hashvec: self,
+                key: k,
+                key_hash
+            })
+        }
+    }
+
+    /// Returns an occupied entry handle for the provided key, if it exists.
+    /// 
+    /// Unlike [`HashVec::entry()`], this doesn't need an owned key. The handle allows the value to be mutated and the entry to be moved or removed, all from this single lookup.
+    pub fn get_mut_entry(&mut self, k: &K) -> Option<OccupiedEntry<'_, K, V>> {
+        let key_hash = calculate_hash(k);
+
+        self.order.get(&key_hash).copied().map(|index| OccupiedEntry {
+            hashvec: self,
+            index,
+            key_hash
+        })
+    }
+
+    /// Returns an occupied entry handle for the entry at the provided index, or `None` if the index exceeds the current length of the hashvec.
+    pub fn index_entry(&mut self, i: usize) -> Option<OccupiedEntry<'_, K, V>> {
+        let key_hash = calculate_hash(&self.entries.get(i)?.0);
+
+        Some(OccupiedEntry {
+            hashvec: self,
+            index: i,
+            key_hash
+        })
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, appending the value type's default first if the key isn't in the hashvec.
+    /// 
+    /// # Panics
+    /// Panics if the key isn't in the hashvec and the hashvec [`is_full()`](HashVec::is_full).
+    pub fn get_or_insert_default(&mut self, k: K) -> &mut V
+    where
+        V: Default
+    {
+        self.entry(k).or_insert_with(V::default)
+    }
+
+    /// Applies `update` to the value corresponding to the key if it exists, or appends `default` otherwise.
+    /// 
+    /// The key is only hashed once, unlike the equivalent combination of [`HashVec::get_mut()`] and [`HashVec::insert()`]. Like [`HashVec::insert()`], if the key is new but the hashvec [`is_full()`](HashVec::is_full), nothing is appended.
+    pub fn update_or_insert<F>(&mut self, k: K, mut update: F, default: V)
+    where
+        F: FnMut(&mut V)
+    {
+        match self.entry(k) {
+            Entry::Occupied(mut entry) => update(entry.get_mut()),
+            Entry::Vacant(entry) => {
+                let _ = entry.try_insert(default);
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, appending the result of `f` first if the key isn't in the hashvec.
+    /// 
+    /// If `f` returns an error, nothing is inserted and the error is returned.
+    /// 
+    /// # Panics
+    /// Panics if the key isn't in the hashvec and the hashvec [`is_full()`](HashVec::is_full).
+    pub fn get_or_try_insert_with<F, E>(&mut self, k: K, f: F) -> Result<&mut V, E>
+    where
+        F: FnOnce() -> Result<V, E>
+    {
+        match self.entry(k) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => Ok(entry.insert(f()?))
+        }
+    }
+
     /// Changes an entry's key, preserving and returning a reference to the associated value.
     /// 
     /// If the hashvec did not have an entry corresponding to the old key, `None` is returned.
     pub fn rename(&mut self, old_key: &K, new_key: K) -> Option<&V> {
         let old_key_hash = calculate_hash(old_key);
 
-        let index_opt = self.order.get(&old_key_hash).map(|index| *index);
+        let index_opt = self.order.get(&old_key_hash).copied();
 
         index_opt.map(|index| {
             let new_key_hash = calculate_hash(&new_key);
@@ -292,59 +916,230 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
         })
     }
 
+    /// Replaces the key and value of the entry at the provided index, returning the old key and value.
+    /// 
+    /// If the index exceeds the current length of the hashvec, or if the new key already belongs to a different entry, the hashvec is left unchanged and `None` is returned.
+    pub fn replace_at(&mut self, index: usize, k: K, v: V) -> Option<(K, V)> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let new_key_hash = calculate_hash(&k);
+        if self.order.get(&new_key_hash).is_some_and(|existing| *existing != index) {
+            return None;
+        }
+
+        let old_entry = std::mem::replace(&mut self.entries[index], (k, v));
+
+        // Stop tracking the old key hash, then start tracking the new one at the same index
+        self.order.remove(&calculate_hash(&old_entry.0));
+        self.order.insert(new_key_hash, index);
+
+        Some(old_entry)
+    }
+
+    /// Renames every key for which `select` returns `true` to the key produced by `transform`, preserving each entry's position and value.
+    /// 
+    /// If the renamed keys collide with each other or with the remaining keys, the collisions are collapsed: only the first entry (in stored order) with each key is kept, and the rest are dropped.
+    pub fn rename_matching<F, G>(&mut self, mut select: F, mut transform: G)
+    where
+        F: FnMut(&K) -> bool,
+        G: FnMut(&K) -> K
+    {
+        for (k, _) in self.entries.iter_mut() {
+            if select(k) {
+                *k = transform(k);
+            }
+        }
+
+        // Collapse any collisions and re-track every key under its new hash
+        self.dedup_keys_keep_first();
+    }
+
     /// Removes a key from the hashvec, returning the stored key and value if the key was previously in the hashvec.
     pub fn remove_key_entry(&mut self, k: &K) -> Option<(K, V)> {
         let key_hash = calculate_hash(k);
 
-        let index_opt = self.order.get(&key_hash).map(|index| *index);
+        let index_opt = self.order.get(&key_hash).copied();
 
-        index_opt.map(|index| {
+        index_opt.map(|index| self.remove_index(index, key_hash))
+    }
+
+    // Removes the entry at the provided index (whose key hash is already known) and updates the tracked index of every entry which followed it
+    fn remove_index(&mut self, index: usize, key_hash: u64) -> (K, V) {
+        // Remove the corresponding entry from the order hashmap
+        self.order.remove(&key_hash);
+
+        let value = if index + 1 == self.entries.len() {
+            // Removing the last entry doesn't shift any others, so there's nothing to re-index
+            self.entries.pop().unwrap()
+        } else {
             // Get the entry and then remove it from the hashvec entirely before returning the value
             let value = self.entries.remove(index);
-                
-            // Remove the corresponding entry from the order hashmap
-            self.order.remove(&key_hash);
 
             // Update the index on all the remaining entries which followed the one we just removed
-            for (i, (k, v)) in self.entries.iter().enumerate() {
-                if i >= index {
-                    self.order.insert(calculate_hash(&self.entries[i].0), i);
-                }
+            for (i, (k, _)) in self.entries.iter().enumerate().skip(index) {
+                self.order.insert(calculate_hash(k), i);
             }
 
-            // Now return the value we retained earlier
             value
-        })
+        };
+
+        self.apply_shrink_policy();
+
+        // Now return the value we retained earlier
+        value
     }
-    
-    // Swaps the positions of entries `a` and `b` within the hashvec.
+
+    // Shrinks the hashvec if its shrink policy calls for it after an entry was removed
+    fn apply_shrink_policy(&mut self) {
+        match self.shrink_policy {
+            ShrinkPolicy::Never => {},
+            ShrinkPolicy::WhenQuarterFull => {
+                if self.len() < self.entries.capacity() / 4 {
+                    self.shrink_to(self.len() * 2);
+                }
+            }
+        }
+    }
+    
+    // Swaps the positions of entries `a` and `b` within the hashvec.
     //pub fn swap(&mut self, a: K, b: K) {
         //
     //}
 
     /// Returns the index of the provided key, if the key exists.
     pub fn index(&self, k: &K) -> Option<usize> {
-        match self.order.get(&calculate_hash(k)) {
-            Some(index) => Some(*index),
-            None => None
+        self.order.get(&calculate_hash(k)).copied()
+    }
+
+    /// Returns the index of the first entry whose value is equal to the provided value, if there is one.
+    pub fn position_by_value(&self, v: &V) -> Option<usize>
+    where
+        V: PartialEq
+    {
+        self.entries.iter().position(|(_, value)| value == v)
+    }
+
+    /// Returns the index of the first entry for which `predicate` returns `true`, if there is one.
+    pub fn position<F>(&self, mut predicate: F) -> Option<usize>
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        self.entries.iter().position(|(k, v)| predicate(k, v))
+    }
+
+    /// Returns the index of the last entry for which `predicate` returns `true`, if there is one.
+    /// 
+    /// Entries are checked from the back of the hashvec.
+    pub fn rposition<F>(&self, mut predicate: F) -> Option<usize>
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        self.entries.iter().rposition(|(k, v)| predicate(k, v))
+    }
+
+    /// Returns the index of the entry with the greatest value, or `None` if the hashvec is empty.
+    /// 
+    /// If several entries share the greatest value, the index of the first one is returned.
+    pub fn position_max_value(&self) -> Option<usize>
+    where
+        V: Ord
+    {
+        let mut best: Option<usize> = None;
+        for (i, (_, v)) in self.entries.iter().enumerate() {
+            if best.is_none_or(|best| *v > self.entries[best].1) {
+                best = Some(i);
+            }
+        }
+        best
+    }
+
+    /// Returns the index of the entry with the least value, or `None` if the hashvec is empty.
+    /// 
+    /// If several entries share the least value, the index of the first one is returned.
+    pub fn position_min_value(&self) -> Option<usize>
+    where
+        V: Ord
+    {
+        let mut best: Option<usize> = None;
+        for (i, (_, v)) in self.entries.iter().enumerate() {
+            if best.is_none_or(|best| *v < self.entries[best].1) {
+                best = Some(i);
+            }
+        }
+        best
+    }
+
+    /// Returns references to the key and value of the entry whose value is greatest according to `compare`, or `None` if the hashvec is empty.
+    /// 
+    /// If several entries compare equally greatest, the first one is returned.
+    pub fn max_value_by<F>(&self, mut compare: F) -> Option<(&K, &V)>
+    where
+        F: FnMut(&V, &V) -> Ordering
+    {
+        let mut best: Option<(&K, &V)> = None;
+        for (k, v) in self.entries.iter() {
+            if best.is_none_or(|(_, best)| compare(v, best) == Ordering::Greater) {
+                best = Some((k, v));
+            }
+        }
+        best
+    }
+
+    /// Returns references to the key and value of the entry whose value is least according to `compare`, or `None` if the hashvec is empty.
+    /// 
+    /// If several entries compare equally least, the first one is returned.
+    pub fn min_value_by<F>(&self, mut compare: F) -> Option<(&K, &V)>
+    where
+        F: FnMut(&V, &V) -> Ordering
+    {
+        let mut best: Option<(&K, &V)> = None;
+        for (k, v) in self.entries.iter() {
+            if best.is_none_or(|(_, best)| compare(v, best) == Ordering::Less) {
+                best = Some((k, v));
+            }
         }
+        best
     }
 
     /// Removes a key from the hashvec, returning the stored value if the key was previously in the hashvec.
     pub fn remove_key(&mut self, k: &K) -> Option<V> {
-        match self.remove_key_entry(k) {
-            Some((_, v)) => Some(v),
-            None => None
+        self.remove_key_entry(k).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the hashvec only if `predicate` returns `true` for its value, returning the removed value.
+    /// 
+    /// Returns `None` and leaves the hashvec untouched if the key is absent or the predicate rejects its value.
+    pub fn remove_if<F>(&mut self, k: &K, predicate: F) -> Option<V>
+    where
+        F: FnOnce(&V) -> bool
+    {
+        let key_hash = calculate_hash(k);
+
+        let index = *self.order.get(&key_hash)?;
+        if !predicate(&self.entries[index].1) {
+            return None;
         }
+
+        Some(self.remove_index(index, key_hash).1)
     }
 
     /// Reserves capacity for at least `additional` more elements to be inserted in the `HashVec`. The collection may reserve more space to avoid frequent reallocations.
     /// 
+    /// The reservation holds for both backing structures: the index map's [`HashMap::reserve()`] already accounts for its load factor, so `additional` new keys can be inserted afterwards without the index map rehashing.
+    /// 
     /// # Panics
     /// Panics if the new capacity either overflows `usize` or exceeds `isize::MAX` bytes.
     pub fn reserve(&mut self, additional: usize) {
+        if additional == 0 {
+            return;
+        }
+
+        let old_capacity = self.entries.capacity();
         self.entries.reserve(additional);
         self.order.reserve(additional);
+        self.notify_grow(old_capacity);
     }
 
     /// Shrinks the capacity of the hashvec with a lower limit.
@@ -352,60 +1147,3137 @@ impl<K: Eq + Hash, V> HashVec<K, V> {
     /// The capacity will remain at least as large as both the length and the supplied value.
     /// 
     /// If the current capacity is less than the lower limit, this is a no-op.
+    /// 
+    /// The lower limit is applied to each backing structure separately, so neither one is ever shrunk below its own length.
     pub fn shrink_to(&mut self, min_capacity: usize) {
-        self.entries.shrink_to(min_capacity);
-        self.order.shrink_to(min_capacity);
+        self.entries.shrink_to(min_capacity.max(self.entries.len()));
+        self.order.shrink_to(min_capacity.max(self.order.len()));
     }
 
     /// Shrinks the capacity of the hashvec as much as possible, according to internal rules.
+    /// 
+    /// Afterwards, the capacity of the entries vector is exactly equal to the length of the hashvec. Shrinking the index map is best-effort, since a [`HashMap`] keeps some slack according to its own load factor.
     pub fn shrink_to_fit(&mut self) {
-        self.entries.shrink_to_fit();
+        if self.entries.capacity() > self.entries.len() {
+            // A boxed slice never has spare capacity, so converting through one guarantees an exact fit
+            self.entries = std::mem::take(&mut self.entries).into_boxed_slice().into_vec();
+        }
         self.order.shrink_to_fit();
     }
-}
 
-impl<K: Eq + Hash, V> Index<usize> for HashVec<K, V> {
-    type Output = (K, V);
-    fn index(&self, i: usize) -> &(K, V) {
-        &self.entries[i]
+    /// Consumes the hashvec and returns an iterator over its owned entries, each paired with its index.
+    pub fn into_iter_indexed(self) -> impl Iterator<Item = (usize, K, V)> {
+        self.entries.into_iter().enumerate().map(|(i, (k, v))| (i, k, v))
     }
-}
 
-impl<'a, K: Eq + Hash, V> IntoIterator for &'a HashVec<K, V> {
-    type Item = (&'a K, &'a V);
-    type IntoIter = HashVecIter<'a, K, V>;
-    fn into_iter(self) -> Self::IntoIter {
+    /// Consumes the hashvec and returns its internal parts: the entries in stored order, and the index map from each key's hash to its entry's index.
+    /// 
+    /// The parts can be reassembled with [`HashVec::from_parts()`], or the entries alone with [`HashVec::from_entries()`].
+    pub fn into_parts(self) -> (Vec<(K, V)>, HashMap<u64, usize>) {
+        (self.entries, self.order)
+    }
+
+    /// Consumes the hashvec and returns its entries as a vector sorted by key.
+    pub fn into_sorted_vec(self) -> Vec<(K, V)>
+    where
+        K: Ord
+    {
+        self.into_sorted_vec_by(|a, b| a.0.cmp(&b.0))
+    }
+
+    /// Consumes the hashvec and returns its entries as a vector sorted with the provided comparator function.
+    /// 
+    /// The sort is stable, so entries which compare equal keep their stored order.
+    pub fn into_sorted_vec_by<F>(self, cmp: F) -> Vec<(K, V)>
+    where
+        F: FnMut(&(K, V), &(K, V)) -> Ordering
+    {
+        let mut entries = self.entries;
+        entries.sort_by(cmp);
+        entries
+    }
+
+    /// Returns each of the hashvec's entries as an `(index, key, value)` triple, in stored order.
+    pub fn to_ordered_pairs(&self) -> Vec<(usize, &K, &V)> {
+        self.entries.iter().enumerate().map(|(i, (k, v))| (i, k, v)).collect()
+    }
+
+    /// Returns references to the hashvec's keys in their stored order.
+    /// 
+    /// This is the canonical way to capture a hashvec's ordering for later comparison, since it doesn't clone any keys.
+    pub fn key_order(&self) -> Vec<&K> {
+        self.entries.iter().map(|(k, _)| k).collect()
+    }
+
+    /// Returns an iterator over mutable references to the hashvec's values, in stored order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            entries: self.entries.iter_mut()
+        }
+    }
+
+    /// Returns an iterator over only the entries for which `f` returns `true`, in stored order.
+    /// 
+    /// This is the read-only counterpart to the hashvec's retaining methods: nothing is removed.
+    pub fn filtered<F>(&self, mut f: F) -> impl Iterator<Item = (&K, &V)>
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        self.entries.iter()
+            .filter(move |(k, v)| f(k, v))
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the hashvec's entries, from the last to the first.
+    pub fn reverse_iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().rev().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over every `step`th entry of the hashvec, starting with the first.
+    /// 
+    /// # Panics
+    /// Panics if `step` is zero.
+    pub fn step_iter(&self, step: usize) -> impl Iterator<Item = (&K, &V)> {
+        assert!(step != 0, "step must be greater than zero");
+        self.entries.iter().step_by(step).map(|(k, v)| (k, v))
+    }
+
+    /// Returns a parallel iterator over mutable references to the hashvec's values.
+    /// 
+    /// Like [`HashVec::values_mut()`], keys are never exposed. This requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_values_mut(&mut self) -> impl ParallelIterator<Item = &mut V>
+    where
+        K: Send,
+        V: Send
+    {
+        self.entries.par_iter_mut().map(|(_, v)| v)
+    }
+
+    /// Returns a cursor positioned at the first entry of the hashvec.
+    pub fn cursor(&self) -> Cursor<'_, K, V> {
+        Cursor {
+            hashvec: self,
+            index: 0
+        }
+    }
+
+    /// Returns a cursor positioned at the first entry of the hashvec, which allows the current value to be mutated.
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, K, V> {
+        CursorMut {
+            hashvec: self,
+            index: 0
+        }
+    }
+
+    /// Returns an iterator over the hashvec's entries which allows their values to be mutated.
+    /// 
+    /// Keys are only ever exposed immutably, since changing them in-place would desynchronize the hashvec's index.
+    pub fn iter_mut(&mut self) -> HashVecIterMut<'_, K, V> {
+        HashVecIterMut {
+            entries: self.entries.iter_mut()
+        }
+    }
+
+    /// Returns an iterator over the hashvec's entries, in stored order.
+    pub fn iter(&self) -> HashVecIter<'_, K, V> {
+        self.iter_from(0)
+    }
+
+    /// Returns an iterator over the hashvec's entries, starting at the provided index.
+    /// 
+    /// If `start` exceeds the current length of the hashvec, the iterator is empty.
+    pub fn iter_from(&self, start: usize) -> HashVecIter<'_, K, V> {
         HashVecIter {
             ordered_map: self,
-            index: 0
+            index: start.min(self.len())
         }
     }
-}
 
-// Wrapping iterator struct
-pub struct HashVecIter<'a, K: Eq + Hash, V> {
-    ordered_map: &'a HashVec<K, V>,
-    index: usize
-}
+    /// Sorts the hashvec's entries so that the most frequently occurring values come first.
+    /// 
+    /// The sort is stable, so entries whose values occur equally often keep their stored order.
+    pub fn sort_by_value_frequency(&mut self)
+    where
+        V: Eq + Hash
+    {
+        // Count how many times each entry's value occurs in the hashvec
+        let frequencies: Vec<usize> = {
+            let mut counts: HashMap<&V, usize> = HashMap::new();
+            for (_, v) in self.entries.iter() {
+                *counts.entry(v).or_insert(0) += 1;
+            }
+            self.entries.iter().map(|(_, v)| counts[v]).collect()
+        };
 
-impl<'a, K: Eq + Hash, V> Iterator for HashVecIter<'a, K, V> {
-    type Item = (&'a K, &'a V);
-    fn next(&mut self) -> Option<Self::Item> {
-        let result = self.ordered_map.entries.get(self.index).map(|(k, v)| (k, v));
-        self.index += 1;
+        // Sort the entries by descending frequency
+        let mut counted: Vec<(usize, (K, V))> = frequencies.into_iter().zip(self.entries.drain(..)).collect();
+        counted.sort_by_key(|(frequency, _)| Reverse(*frequency));
+        self.entries.extend(counted.into_iter().map(|(_, entry)| entry));
+
+        self.rebuild_index();
+    }
+
+    /// Removes the entries in the provided index range from the hashvec, returning them as an iterator.
+    /// 
+    /// The whole range is removed even if the iterator is dropped before it's fully consumed, and the following entries are re-indexed once it's dropped. If the iterator is leaked instead (with [`std::mem::forget()`]), the hashvec is left holding only the entries before the range, just like [`Vec::drain()`].
+    /// 
+    /// # Panics
+    /// Panics if the start of the range is greater than its end, or if its end is greater than the length of the hashvec.
+    pub fn drain_range<R>(&mut self, range: R) -> DrainRange<'_, K, V>
+    where
+        R: RangeBounds<usize>
+    {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.checked_add(1)
+                .unwrap_or_else(|| panic!("drain range start {} is out of bounds for a hashvec of length {}", start, len)),
+            Bound::Unbounded => 0
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.checked_add(1)
+                .unwrap_or_else(|| panic!("drain range end {} is out of bounds for a hashvec of length {}", end, len)),
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => len
+        };
+        assert!(start <= end, "drain range starts at {} but ends at {}", start, end);
+        assert!(end <= len, "drain range end {} is out of bounds for a hashvec of length {}", end, len);
+
+        // Stop tracking the drained entries' keys, and the following ones' until the drain is dropped and they've shifted back
+        for (k, _) in self.entries[start..end].iter() {
+            self.order.remove(&calculate_hash(k));
+        }
+        let tail_hashes: Vec<u64> = self.entries[end..].iter()
+            .map(|(k, _)| {
+                let key_hash = calculate_hash(k);
+                self.order.remove(&key_hash);
+                key_hash
+            })
+            .collect();
+
+        DrainRange {
+            entries: self.entries.drain(start..end),
+            order: &mut self.order,
+            start,
+            tail_hashes
+        }
+    }
+
+    /// Moves the provided key's entry to the back of the hashvec without changing its value, shifting all following entries forward.
+    /// 
+    /// Returns `false` (and does nothing) if the key is not in the hashvec.
+    pub fn touch(&mut self, k: &K) -> bool {
+        match self.index(k) {
+            Some(index) => {
+                self.move_index(index, self.len() - 1);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Moves the provided key's entry to the back of the hashvec (like [`HashVec::touch()`]), then returns a reference to its value.
+    pub fn get_and_touch(&mut self, k: &K) -> Option<&V> {
+        if self.touch(k) {
+            self.entries.last().map(|(_, v)| v)
+        } else {
+            None
+        }
+    }
+
+    /// Removes up to `count` entries from the front of the hashvec (the oldest ones, if entries are only ever pushed) and returns them in their stored order.
+    /// 
+    /// If the hashvec holds fewer than `count` entries, all of them are removed.
+    pub fn evict_oldest(&mut self, count: usize) -> Vec<(K, V)> {
+        let count = count.min(self.len());
+        self.drain_range(..count).collect()
+    }
+
+    /// Removes every entry for which `f` returns `true`, returning the removed keys in their stored order.
+    /// 
+    /// The remaining entries keep their stored relative order.
+    pub fn evict<F>(&mut self, mut f: F) -> Vec<K>
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        let mut evicted: Vec<K> = Vec::new();
+        let mut survivors: Vec<(K, V)> = Vec::with_capacity(self.entries.len());
+
+        for (k, v) in self.entries.drain(..) {
+            if f(&k, &v) {
+                evicted.push(k);
+            } else {
+                survivors.push((k, v));
+            }
+        }
+
+        self.entries = survivors;
+        self.rebuild_index();
+        evicted
+    }
+
+    /// Removes every entry whose value is considered dead by `is_dead`, rebuilding the index once afterwards.
+    /// 
+    /// This is intended for compacting a hashvec after values have been marked as tombstones. The remaining entries keep their stored relative order.
+    pub fn compact<F>(&mut self, mut is_dead: F)
+    where
+        F: FnMut(&V) -> bool
+    {
+        let old_len = self.len();
+        self.entries.retain(|(_, v)| !is_dead(v));
+
+        if self.len() != old_len {
+            self.rebuild_index();
+        }
+    }
+
+    /// Retains only the entries for which `f` returns `true`, removing all others.
+    /// 
+    /// The remaining entries keep their stored relative order. The entries are compacted in place, and the index is rebuilt once afterwards rather than after every removal.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        let old_len = self.len();
+        self.entries.retain(|(k, v)| f(k, v));
+
+        if self.len() != old_len {
+            self.rebuild_index();
+        }
+    }
+
+    /// Retains only the entries for which `f` returns `true` (just like [`HashVec::retain()`]), and returns `true` if at least one entry was removed.
+    pub fn retain_changed<F>(&mut self, f: F) -> bool
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        let old_len = self.len();
+        self.retain(f);
+        self.len() != old_len
+    }
+
+    /// Retains only the entries for which `f` returns `true`, and returns the old index of each surviving entry in its new order.
+    /// 
+    /// This is useful for re-indexing external data which runs parallel to the hashvec's entries.
+    pub fn retain_with_mapping<F>(&mut self, mut f: F) -> Vec<usize>
+    where
+        F: FnMut(&K, &V) -> bool
+    {
+        let mut mapping: Vec<usize> = Vec::with_capacity(self.len());
+        let mut i = 0;
+        self.entries.retain(|(k, v)| {
+            let keep = f(k, v);
+            if keep {
+                mapping.push(i);
+            }
+            i += 1;
+            keep
+        });
+
+        self.rebuild_index();
+        mapping
+    }
+
+    /// Retains only the entries for which `f` returns `Ok(true)`, stopping at the first error.
+    /// 
+    /// If `f` returns an error, that error is returned and the hashvec is left partially processed: entries before the one which caused the error have been removed or kept according to `f`, while that entry and every one after it are kept untouched. Either way, the hashvec's index is left consistent.
+    pub fn try_retain<F, E>(&mut self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(&K, &V) -> Result<bool, E>
+    {
+        let old_len = self.len();
+        let mut result = Ok(());
+        self.entries.retain(|(k, v)| {
+            if result.is_err() {
+                return true;
+            }
+
+            match f(k, v) {
+                Ok(keep) => keep,
+                Err(error) => {
+                    result = Err(error);
+                    true
+                }
+            }
+        });
+
+        if self.len() != old_len {
+            self.rebuild_index();
+        }
         result
     }
+
+    /// Retains only the `n` greatest entries according to the provided comparator function, removing all others.
+    /// 
+    /// The surviving entries keep their stored relative order. If `n` is at least the length of the hashvec, this is a no-op.
+    pub fn retain_top_n<F>(&mut self, n: usize, mut compare: F)
+    where
+        F: FnMut(&(K, V), &(K, V)) -> Ordering
+    {
+        let len = self.len();
+        if n >= len {
+            return;
+        }
+        if n == 0 {
+            self.clear();
+            return;
+        }
+
+        // Partially select the indices of the n greatest entries, without fully sorting them
+        let mut ranked: Vec<usize> = (0..len).collect();
+        ranked.select_nth_unstable_by(n - 1, |a, b| compare(&self.entries[*b], &self.entries[*a]));
+        let mut keep = vec![false; len];
+        for i in &ranked[..n] {
+            keep[*i] = true;
+        }
+
+        // Remove every entry which wasn't selected
+        let mut i = 0;
+        self.entries.retain(|_| {
+            i += 1;
+            keep[i - 1]
+        });
+
+        self.rebuild_index();
+    }
+
+    /// Returns the number of keys tracked by the hashvec's internal index map.
+    /// 
+    /// For a consistent hashvec, this is always equal to [`HashVec::len()`].
+    pub fn index_map_len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Checks that the hashvec's internal index map agrees with its entries.
+    /// 
+    /// This is intended as a debugging aid while developing code which reorders entries.
+    /// 
+    /// # Panics
+    /// Panics with a description of the problem if the index map tracks a different number of keys than there are entries, if any tracked index is out of range, or if any entry's key isn't tracked at its actual index.
+    pub fn assert_consistent(&self) {
+        assert_eq!(
+            self.order.len(),
+            self.entries.len(),
+            "hashvec index map tracks {} keys, but there are {} entries",
+            self.order.len(),
+            self.entries.len()
+        );
+
+        for (key_hash, index) in self.order.iter() {
+            assert!(
+                *index < self.entries.len(),
+                "hashvec index map maps key hash {} to index {}, which is out of range for {} entries",
+                key_hash,
+                index,
+                self.entries.len()
+            );
+        }
+
+        for (i, (k, _)) in self.entries.iter().enumerate() {
+            let tracked = self.order.get(&calculate_hash(k));
+            assert_eq!(
+                tracked,
+                Some(&i),
+                "hashvec entry at index {} is tracked at {:?} in the index map",
+                i,
+                tracked
+            );
+        }
+    }
+
+    /// Returns a standard [`HashMap`] which borrows each of the hashvec's keys and values, discarding their order.
+    pub fn as_unordered(&self) -> HashMap<&K, &V> {
+        self.entries.iter().map(|(k, v)| (k, v)).collect()
+    }
+
+    /// Consumes the hashvec and returns a new one whose keys are the old values and whose values are the old keys, in the same stored order.
+    /// 
+    /// Where several entries share a value, the last of them wins: the flipped entry keeps the position of the value's first occurrence, but holds the key of its last occurrence.
+    pub fn flip(self) -> HashVec<V, K>
+    where
+        V: Eq + Hash
+    {
+        let mut flipped = HashVec::with_capacity(self.len());
+        for (k, v) in self.entries {
+            flipped.insert(v, k);
+        }
+        flipped
+    }
+
+    /// Joins the hashvec with `other` on their shared keys, pairing up references to both values in `Self`'s stored order.
+    /// 
+    /// Keys which aren't present in `other` are skipped.
+    pub fn zip_values<'a, W>(&'a self, other: &'a HashVec<K, W>) -> HashVec<K, (&'a V, &'a W)>
+    where
+        K: Clone
+    {
+        let mut zipped = HashVec::new();
+        for (k, v) in self.entries.iter() {
+            if let Some(w) = other.get(k) {
+                zipped.insert(k.clone(), (v, w));
+            }
+        }
+        zipped
+    }
+
+    /// Returns references to the hashvec's keys, sorted in ascending order.
+    /// 
+    /// The hashvec's stored order is left untouched.
+    pub fn keys_sorted(&self) -> Vec<&K>
+    where
+        K: Ord
+    {
+        let mut keys: Vec<&K> = self.entries.iter().map(|(k, _)| k).collect();
+        keys.sort();
+        keys
+    }
+
+    /// Returns references to the hashvec's values, sorted in ascending order.
+    /// 
+    /// The hashvec's stored order is left untouched.
+    pub fn values_sorted(&self) -> Vec<&V>
+    where
+        V: Ord
+    {
+        let mut values: Vec<&V> = self.entries.iter().map(|(_, v)| v).collect();
+        values.sort();
+        values
+    }
+
+    // Moves the entry at one index to another, shifting the entries in between and updating their tracked indices
+    fn move_index(&mut self, from: usize, to: usize) {
+        if from < to {
+            self.entries[from..=to].rotate_left(1);
+        } else {
+            self.entries[to..=from].rotate_right(1);
+        }
+
+        for i in from.min(to)..=from.max(to) {
+            self.order.insert(calculate_hash(&self.entries[i].0), i);
+        }
+    }
+
+    /// Removes every entry whose key already appeared at an earlier index, keeping only the first occurrence of each key, and rebuilds the index.
+    /// 
+    /// This is a cleanup pass for hashvecs built through low-level constructors like [`HashVec::from_entries()`], which don't resolve redundant keys.
+    pub fn dedup_keys_keep_first(&mut self) {
+        let mut seen: HashSet<u64> = HashSet::with_capacity(self.len());
+        self.entries.retain(|(k, _)| seen.insert(calculate_hash(k)));
+        self.rebuild_index();
+    }
+
+    /// Removes every entry which is equal, in both key and value, to the entry immediately before it, and rebuilds the index.
+    /// 
+    /// Since keys are unique in a consistent hashvec, this mainly matters for hashvecs built through low-level constructors like [`HashVec::from_entries()`].
+    pub fn dedup(&mut self)
+    where
+        K: PartialEq,
+        V: PartialEq
+    {
+        let old_len = self.len();
+        self.entries.dedup();
+
+        if self.len() != old_len {
+            self.rebuild_index();
+        }
+    }
+
+    /// Sorts the hashvec's entries by the projection `f`, computing it only once per entry, and rebuilds the index.
+    /// 
+    /// Like [`slice::sort_by_cached_key()`], this is worthwhile when the projection is expensive. The sort is stable.
+    pub fn sort_by_cached_key<T: Ord, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &V) -> T
+    {
+        self.entries.sort_by_cached_key(|(k, v)| f(k, v));
+        self.rebuild_index();
+    }
+
+    /// Sorts the hashvec's entries by key, then collapses any runs of equal keys into a single entry.
+    /// 
+    /// The sort is stable, and the last entry of each run is the one which is kept.
+    pub fn sort_and_dedup(&mut self)
+    where
+        K: Ord
+    {
+        self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+        self.entries.dedup_by(|later, kept| {
+            if later.0 == kept.0 {
+                // Keep the later entry in place of the earlier one
+                std::mem::swap(later, kept);
+                true
+            } else {
+                false
+            }
+        });
+
+        self.rebuild_index();
+    }
+
+    /// Returns a new hashvec containing clones of only the entries whose keys are listed in `keys`.
+    /// 
+    /// The entries keep `Self`'s relative order, rather than the order of `keys`. Listed keys which aren't in the hashvec are ignored.
+    pub fn subset(&self, keys: &[K]) -> HashVec<K, V>
+    where
+        K: Clone,
+        V: Clone
+    {
+        let wanted: HashSet<u64> = keys.iter().map(calculate_hash).collect();
+        self.entries.iter()
+            .filter(|(k, _)| wanted.contains(&calculate_hash(k)))
+            .cloned()
+            .collect()
+    }
+
+    /// Clones the hashvec's entries, in stored order, into a reference-counted slice.
+    /// 
+    /// The snapshot can be shared (including across threads) and iterated without holding on to the hashvec itself.
+    pub fn to_arc_slice(&self) -> Arc<[(K, V)]>
+    where
+        K: Clone,
+        V: Clone
+    {
+        Arc::from(self.entries.as_slice())
+    }
+
+    /// Returns the range of indices of the entries whose projection through `f` is equal to `key`, using binary search.
+    /// 
+    /// The hashvec must already be sorted by the same projection. If it isn't, the returned range is unspecified and meaningless. If no entries match, the range is empty and starts where a matching entry would be inserted.
+    pub fn equal_range_by_key<F, T: Ord>(&self, key: &T, mut f: F) -> Range<usize>
+    where
+        F: FnMut(&K, &V) -> T
+    {
+        let start = self.entries.partition_point(|(k, v)| f(k, v) < *key);
+        let end = start + self.entries[start..].partition_point(|(k, v)| f(k, v) <= *key);
+        start..end
+    }
+
+    /// Returns `true` if the hashvec's entries are equal to the provided slice of key-value pairs, in order.
+    pub fn entries_eq(&self, expected: &[(K, V)]) -> bool
+    where
+        K: PartialEq,
+        V: PartialEq
+    {
+        self.entries.as_slice() == expected
+    }
+
+    // Recalculates the tracked index of every entry in the hashvec
+    fn rebuild_index(&mut self) {
+        self.order.clear();
+        for (i, (k, _)) in self.entries.iter().enumerate() {
+            self.order.insert(calculate_hash(k), i);
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, or a [`KeyNotFound`] error if it doesn't exist.
+    pub fn try_get(&self, k: &K) -> Result<&V, KeyNotFound> {
+        self.get(k).ok_or(KeyNotFound)
+    }
+
+    /// Returns references to the key and value of the entry at the provided index, or an [`IndexOutOfBounds`] error if the index exceeds the current length of the hashvec.
+    pub fn try_get_index(&self, i: usize) -> Result<(&K, &V), IndexOutOfBounds> {
+        self.entries.get(i).map(|(k, v)| (k, v)).ok_or(IndexOutOfBounds {
+            index: i,
+            len: self.len()
+        })
+    }
+
 }
 
-fn calculate_hash<K: Hash>(k: &K)-> u64 {
-    let mut hasher = DefaultHasher::new();
-    k.hash(&mut hasher);
-    hasher.finish()
+impl<K: Eq + Hash + fmt::Debug, V: fmt::Debug> fmt::Debug for HashVec<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashVec")
+            .field("entries", &self.entries)
+            .field("order", &self.order)
+            .finish()
+    }
 }
 
-#[macro_export]
-macro_rules! hashvec {
-    ($($x:expr),+ $(,)?) => (
-        HashVec::from_vec(vec![$($x),+])
-    );
-}
\ No newline at end of file
+impl<K: Eq + Hash + Clone, V: Clone> Clone for HashVec<K, V> {
+    /// Clones the hashvec, sizing the clone to the original's length rather than its capacity, so any spare capacity isn't propagated.
+    /// 
+    /// The clone keeps the original's maximum length and shrink policy, but not its grow callback, which can't be cloned.
+    fn clone(&self) -> HashVec<K, V> {
+        let mut new_hashvec = HashVec::with_capacity(self.len());
+        new_hashvec.entries.extend(self.entries.iter().cloned());
+        new_hashvec.order.extend(self.order.iter().map(|(key_hash, index)| (*key_hash, *index)));
+        new_hashvec.max_len = self.max_len;
+        new_hashvec.shrink_policy = self.shrink_policy;
+        new_hashvec
+    }
+}
+
+impl<K: Eq + Hash, V> Default for HashVec<K, V> {
+    fn default() -> HashVec<K, V> {
+        HashVec::new()
+    }
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for HashVec<K, V> {
+    /// Creates a hashvec from an iterator of key-value pairs.
+    /// 
+    /// Like [`HashVec::from_vec()`], redundant keys' entries will be overwritten and moved to the end of the hashvec sequentially.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> HashVec<K, V> {
+        let mut new_hashvec = HashVec::new();
+        new_hashvec.extend(iter);
+        new_hashvec
+    }
+}
+
+impl<K: Eq + Hash, V> Extend<(K, V)> for HashVec<K, V> {
+    /// Pushes each key-value pair from the iterator onto the hashvec.
+    /// 
+    /// Both backing structures are reserved up front using the iterator's lower size bound, so exact-size sources are collected without reallocating.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for entry in iter {
+            self.push(entry);
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash + Clone, V: Clone> Extend<(&'a K, &'a V)> for HashVec<K, V> {
+    /// Clones each key-value pair from the iterator and pushes it onto the hashvec, just like the owned [`Extend`] implementation.
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+impl<K: Eq + Hash, V> TryFrom<Vec<(K, V)>> for HashVec<K, V> {
+    type Error = DuplicateKeys<K>;
+
+    /// Creates a hashvec from a vector of key-value pairs, as long as none of its keys are redundant.
+    /// 
+    /// If any key appears more than once, no hashvec is built and every redundant occurrence is returned in the error.
+    fn try_from(v: Vec<(K, V)>) -> Result<HashVec<K, V>, DuplicateKeys<K>> {
+        let mut new_hashvec = HashVec::with_capacity(v.len());
+        let mut duplicates: Vec<K> = Vec::new();
+
+        for (k, v) in v {
+            if new_hashvec.contains_key(&k) {
+                duplicates.push(k);
+            } else {
+                new_hashvec.push((k, v));
+            }
+        }
+
+        if duplicates.is_empty() {
+            Ok(new_hashvec)
+        } else {
+            Err(DuplicateKeys { keys: duplicates })
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> Index<usize> for HashVec<K, V> {
+    type Output = (K, V);
+    fn index(&self, i: usize) -> &(K, V) {
+        &self.entries[i]
+    }
+}
+
+impl<'a, K: Eq + Hash, V> IntoIterator for &'a HashVec<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = HashVecIter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        HashVecIter {
+            ordered_map: self,
+            index: 0
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V> IntoIterator for &'a mut HashVec<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = HashVecIterMut<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// Wrapping iterator struct
+pub struct HashVecIter<'a, K: Eq + Hash, V> {
+    ordered_map: &'a HashVec<K, V>,
+    index: usize
+}
+
+impl<'a, K: Eq + Hash, V> Iterator for HashVecIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.ordered_map.entries.get(self.index).map(|(k, v)| (k, v));
+        self.index += 1;
+        result
+    }
+}
+
+// Wrapping mutable iterator struct
+pub struct HashVecIterMut<'a, K: Eq + Hash, V> {
+    entries: std::slice::IterMut<'a, (K, V)>
+}
+
+impl<'a, K: Eq + Hash, V> Iterator for HashVecIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(k, v)| (&*k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K: Eq + Hash, V> DoubleEndedIterator for HashVecIterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<'a, K: Eq + Hash, V> ExactSizeIterator for HashVecIterMut<'a, K, V> {}
+
+impl<'a, K: Eq + Hash, V> FusedIterator for HashVecIterMut<'a, K, V> {}
+
+/// An iterator over mutable references to the values of a hashvec, in stored order.
+/// 
+/// Keys are never exposed, so they can't be changed in a way that would desynchronize the hashvec's index. This is constructed by [`HashVec::values_mut()`].
+pub struct ValuesMut<'a, K, V> {
+    entries: std::slice::IterMut<'a, (K, V)>
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for ValuesMut<'a, K, V> {}
+
+/// A draining iterator over a range of a hashvec's entries, which removes them as it goes.
+/// 
+/// This is constructed by [`HashVec::drain_range()`].
+pub struct DrainRange<'a, K, V> {
+    entries: std::vec::Drain<'a, (K, V)>,
+    order: &'a mut HashMap<u64, usize>,
+    start: usize,
+    tail_hashes: Vec<u64>
+}
+
+impl<'a, K, V> Iterator for DrainRange<'a, K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for DrainRange<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for DrainRange<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for DrainRange<'a, K, V> {}
+
+impl<'a, K, V> Drop for DrainRange<'a, K, V> {
+    fn drop(&mut self) {
+        // Once the drain is dropped the entries which followed the range shift back right after its start, so track them there
+        for (i, key_hash) in self.tail_hashes.iter().enumerate() {
+            self.order.insert(*key_hash, self.start + i);
+        }
+    }
+}
+
+/// A cursor over the entries of a hashvec, which can be moved back and forth between them.
+/// 
+/// The cursor's position is clamped to the hashvec's first and last entries. This is constructed by [`HashVec::cursor()`].
+pub struct Cursor<'a, K: Eq + Hash, V> {
+    hashvec: &'a HashVec<K, V>,
+    index: usize
+}
+
+impl<'a, K: Eq + Hash, V> Cursor<'a, K, V> {
+    /// Moves the cursor to the next entry, unless it's already at the last one.
+    pub fn move_next(&mut self) {
+        if self.index + 1 < self.hashvec.len() {
+            self.index += 1;
+        }
+    }
+
+    /// Moves the cursor to the previous entry, unless it's already at the first one.
+    pub fn move_prev(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    /// Returns references to the key and value of the entry under the cursor (or `None` if the hashvec is empty).
+    pub fn current(&self) -> Option<(&'a K, &'a V)> {
+        self.hashvec.entries.get(self.index).map(|(k, v)| (k, v))
+    }
+}
+
+/// A cursor over the entries of a hashvec, which can be moved back and forth between them and allows the current value to be mutated.
+/// 
+/// The cursor's position is clamped to the hashvec's first and last entries. This is constructed by [`HashVec::cursor_mut()`].
+pub struct CursorMut<'a, K: Eq + Hash, V> {
+    hashvec: &'a mut HashVec<K, V>,
+    index: usize
+}
+
+impl<'a, K: Eq + Hash, V> CursorMut<'a, K, V> {
+    /// Moves the cursor to the next entry, unless it's already at the last one.
+    pub fn move_next(&mut self) {
+        if self.index + 1 < self.hashvec.len() {
+            self.index += 1;
+        }
+    }
+
+    /// Moves the cursor to the previous entry, unless it's already at the first one.
+    pub fn move_prev(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    /// Returns references to the key and value of the entry under the cursor (or `None` if the hashvec is empty).
+    pub fn current(&self) -> Option<(&K, &V)> {
+        self.hashvec.entries.get(self.index).map(|(k, v)| (k, v))
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value of the entry under the cursor (or `None` if the hashvec is empty).
+    pub fn current_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.hashvec.entries.get_mut(self.index).map(|(k, v)| (&*k, v))
+    }
+}
+
+/// A view into a single entry in a hashvec, which may either be occupied or vacant.
+/// 
+/// This is constructed by [`HashVec::entry()`].
+pub enum Entry<'a, K: Eq + Hash, V> {
+    /// An entry whose key is already in the hashvec.
+    Occupied(OccupiedEntry<'a, K, V>),
+    /// An entry whose key isn't in the hashvec yet.
+    Vacant(VacantEntry<'a, K, V>)
+}
+
+impl<'a, K: Eq + Hash, V> Entry<'a, K, V> {
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key()
+        }
+    }
+
+    /// Ensures a value is in the entry by appending the default if it's vacant, and returns a mutable reference to the value.
+    /// 
+    /// # Panics
+    /// Panics if the entry is vacant and the hashvec [`is_full()`](HashVec::is_full).
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default)
+        }
+    }
+
+    /// Ensures a value is in the entry by appending the result of the default function if it's vacant, and returns a mutable reference to the value.
+    /// 
+    /// # Panics
+    /// Panics if the entry is vacant and the hashvec [`is_full()`](HashVec::is_full).
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default())
+        }
+    }
+
+    /// Ensures a value is in the entry by appending the result of the default function if it's vacant, and returns a mutable reference to the value.
+    /// 
+    /// Unlike [`Entry::or_insert_with()`], the default function is given a reference to the entry's key.
+    /// 
+    /// # Panics
+    /// Panics if the entry is vacant and the hashvec [`is_full()`](HashVec::is_full).
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by appending the result of the default function if it's vacant, and returns the entry's index along with a mutable reference to the value.
+    /// 
+    /// The default function is given the index the new entry will occupy (the current length of the hashvec).
+    /// 
+    /// # Panics
+    /// Panics if the entry is vacant and the hashvec [`is_full()`](HashVec::is_full).
+    pub fn or_insert_with_index<F: FnOnce(usize) -> V>(self, f: F) -> (usize, &'a mut V) {
+        match self {
+            Entry::Occupied(entry) => (entry.index(), entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let index = entry.hashvec.len();
+                (index, entry.insert(f(index)))
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into the hashvec.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Entry<'a, K, V> {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            },
+            Entry::Vacant(entry) => Entry::Vacant(entry)
+        }
+    }
+}
+
+/// A view into an occupied entry in a hashvec. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K: Eq + Hash, V> {
+    hashvec: &'a mut HashVec<K, V>,
+    index: usize,
+    key_hash: u64
+}
+
+impl<'a, K: Eq + Hash, V> OccupiedEntry<'a, K, V> {
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &K {
+        &self.hashvec.entries[self.index].0
+    }
+
+    /// Returns the index of this entry within the hashvec.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to this entry's value.
+    pub fn get(&self) -> &V {
+        &self.hashvec.entries[self.index].1
+    }
+
+    /// Returns a mutable reference to this entry's value.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.hashvec.entries[self.index].1
+    }
+
+    /// Converts the entry into a mutable reference to its value, with a lifetime bound to the hashvec itself.
+    /// 
+    /// The entry stays where it is, like an overwrite with [`HashVec::insert()`].
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.hashvec.entries[self.index].1
+    }
+
+    /// Moves the entry to the back of the hashvec, then converts it into a mutable reference to its value, with a lifetime bound to the hashvec itself.
+    /// 
+    /// The entries which followed this one shift forward, like an overwrite with [`HashVec::push()`].
+    pub fn into_mut_pushed(self) -> &'a mut V {
+        let last = self.hashvec.len() - 1;
+        self.move_to(last).into_mut()
+    }
+
+    /// Sets the value of the entry in-place, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Moves the entry to the provided index, shifting the entries in between, and returns the relocated entry.
+    /// 
+    /// # Panics
+    /// Panics if `index` is not less than the length of the hashvec.
+    pub fn move_to(self, index: usize) -> OccupiedEntry<'a, K, V> {
+        assert!(index < self.hashvec.len(), "index {} is out of bounds for a hashvec of length {}", index, self.hashvec.len());
+        self.hashvec.move_index(self.index, index);
+        OccupiedEntry {
+            hashvec: self.hashvec,
+            index,
+            key_hash: self.key_hash
+        }
+    }
+
+    /// Changes the entry's key in-place, preserving its position and value, and returns the renamed entry.
+    /// 
+    /// If the new key already belongs to a different entry, nothing is changed and the new key is handed back along with a [`RekeyError`].
+    pub fn rekey(self, new_key: K) -> Result<OccupiedEntry<'a, K, V>, (K, RekeyError)> {
+        let new_key_hash = calculate_hash(&new_key);
+
+        if let Some(existing) = self.hashvec.order.get(&new_key_hash) {
+            if *existing != self.index {
+                return Err((new_key, RekeyError {
+                    index: *existing
+                }));
+            }
+        }
+
+        // Swap in the new key, then move the tracked index over to its hash
+        self.hashvec.entries[self.index].0 = new_key;
+        self.hashvec.order.remove(&self.key_hash);
+        self.hashvec.order.insert(new_key_hash, self.index);
+
+        Ok(OccupiedEntry {
+            hashvec: self.hashvec,
+            index: self.index,
+            key_hash: new_key_hash
+        })
+    }
+
+    /// Removes the entry from the hashvec, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes the entry from the hashvec, returning its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.hashvec.remove_index(self.index, self.key_hash)
+    }
+}
+
+/// A view into a vacant entry in a hashvec. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K: Eq + Hash, V> {
+    hashvec: &'a mut HashVec<K, V>,
+    key: K,
+    key_hash: u64
+}
+
+impl<'a, K: Eq + Hash, V> VacantEntry<'a, K, V> {
+    /// Returns a reference to the key which would be used when inserting a value through this entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key back out of the entry, without inserting anything.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Appends the entry to the back of the hashvec with the provided value, and returns a mutable reference to it.
+    /// 
+    /// New entries always go at the back, whether they're inserted through this or through [`Entry::or_insert()`] and its siblings. To put one elsewhere, use [`VacantEntry::insert_at()`].
+    /// 
+    /// # Panics
+    /// Panics if the hashvec [`is_full()`](HashVec::is_full). Use [`VacantEntry::try_insert()`] to get the key and value back instead.
+    pub fn insert(self, value: V) -> &'a mut V {
+        match self.try_insert(value) {
+            Ok(value) => value,
+            Err(_) => panic!("cannot insert a new key into a full hashvec")
+        }
+    }
+
+    /// Appends the entry to the back of the hashvec with the provided value (just like [`VacantEntry::insert()`]), and returns a mutable reference to it.
+    /// 
+    /// If the hashvec [`is_full()`](HashVec::is_full), nothing is inserted and the key and value are handed back as an error instead of panicking.
+    pub fn try_insert(self, value: V) -> Result<&'a mut V, (K, V)> {
+        let index = self.hashvec.push_entry(self.key_hash, (self.key, value))?;
+        Ok(&mut self.hashvec.entries[index].1)
+    }
+
+    /// Inserts the entry into the hashvec at the provided index with the provided value, shifting all following entries back, and returns a mutable reference to the value.
+    /// 
+    /// # Panics
+    /// Panics if `index` is greater than the length of the hashvec, or if the hashvec [`is_full()`](HashVec::is_full).
+    pub fn insert_at(self, index: usize, value: V) -> &'a mut V {
+        let len = self.hashvec.len();
+        assert!(index <= len, "index {} is out of bounds for inserting into a hashvec of length {}", index, len);
+
+        if self.hashvec.push_entry(self.key_hash, (self.key, value)).is_err() {
+            panic!("cannot insert a new key into a full hashvec");
+        }
+        if index < len {
+            self.hashvec.move_index(len, index);
+        }
+        &mut self.hashvec.entries[index].1
+    }
+}
+
+/// The error returned when a key isn't present in a hashvec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyNotFound;
+
+impl fmt::Display for KeyNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key not found in hashvec")
+    }
+}
+
+impl Error for KeyNotFound {}
+
+/// The error returned when an index exceeds the length of a hashvec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexOutOfBounds {
+    /// The index which was requested.
+    pub index: usize,
+    /// The length of the hashvec at the time of the request.
+    pub len: usize
+}
+
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "index {} is out of bounds for a hashvec of length {}", self.index, self.len)
+    }
+}
+
+impl Error for IndexOutOfBounds {}
+
+/// The error returned when an entry can't be given a new key, because that key already belongs to a different entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RekeyError {
+    /// The index of the entry which already uses the new key.
+    pub index: usize
+}
+
+impl fmt::Display for RekeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key already belongs to the hashvec entry at index {}", self.index)
+    }
+}
+
+impl Error for RekeyError {}
+
+/// The error returned when converting into a hashvec from data containing redundant keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKeys<K> {
+    /// Every redundant occurrence of a key, in the order they were encountered.
+    pub keys: Vec<K>
+}
+
+impl<K> fmt::Display for DuplicateKeys<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "found {} duplicate key(s) while building hashvec", self.keys.len())
+    }
+}
+
+impl<K: fmt::Debug> Error for DuplicateKeys<K> {}
+
+fn calculate_hash<K: Hash + ?Sized>(k: &K)-> u64 {
+    let mut hasher = DefaultHasher::new();
+    k.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[macro_export]
+macro_rules! hashvec {
+    ($($x:expr),+ $(,)?) => (
+        HashVec::from_vec(vec![$($x),+])
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_sorted_vec_sorts_by_key() {
+        let hashvec: HashVec<&str, i32> = hashvec![("c", 1), ("a", 3), ("b", 2)];
+        assert_eq!(hashvec.into_sorted_vec(), vec![("a", 3), ("b", 2), ("c", 1)]);
+    }
+
+    #[test]
+    fn into_sorted_vec_by_uses_comparator() {
+        let hashvec: HashVec<&str, i32> = hashvec![("c", 1), ("a", 3), ("b", 2), ("d", 1)];
+        let sorted = hashvec.into_sorted_vec_by(|a, b| b.1.cmp(&a.1));
+
+        // Equal values keep their stored order
+        assert_eq!(sorted, vec![("a", 3), ("b", 2), ("c", 1), ("d", 1)]);
+    }
+
+    #[test]
+    fn singleton_holds_one_entry() {
+        let hashvec = HashVec::singleton("a", 1);
+        assert_eq!(hashvec.len(), 1);
+        assert_eq!(hashvec.get(&"a"), Some(&1));
+        assert_eq!(hashvec[0], ("a", 1));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn iter_from_yields_the_tail() {
+        let hashvec: HashVec<i32, i32> = (0..6).map(|i| (i, i * 10)).collect();
+        let tail: Vec<(&i32, &i32)> = hashvec.iter_from(3).collect();
+        let expected: Vec<(&i32, &i32)> = hashvec.entries[3..].iter().map(|(k, v)| (k, v)).collect();
+        assert_eq!(tail, expected);
+    }
+
+    #[test]
+    fn iter_from_is_clamped_to_len() {
+        let hashvec: HashVec<i32, i32> = (0..3).map(|i| (i, i)).collect();
+        assert_eq!(hashvec.iter_from(3).count(), 0);
+        assert_eq!(hashvec.iter_from(100).count(), 0);
+    }
+
+    #[test]
+    fn sort_by_value_frequency_puts_common_values_first() {
+        let mut hashvec: HashVec<&str, char> = hashvec![
+            ("a", 'x'),
+            ("b", 'y'),
+            ("c", 'z'),
+            ("d", 'y'),
+            ("e", 'x'),
+            ("f", 'y')
+        ];
+        hashvec.sort_by_value_frequency();
+
+        // 'y' occurs three times, 'x' twice and 'z' once, and ties keep their stored order
+        let keys: Vec<&str> = hashvec.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["b", "d", "f", "a", "e", "c"]);
+        assert_eq!(hashvec.index(&"c"), Some(5));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn try_get_reports_missing_keys() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        assert_eq!(hashvec.try_get(&"a"), Ok(&1));
+        assert_eq!(hashvec.try_get(&"b"), Err(KeyNotFound));
+        assert_eq!(KeyNotFound.to_string(), "key not found in hashvec");
+    }
+
+    #[test]
+    fn try_get_index_reports_out_of_bounds() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+        assert_eq!(hashvec.try_get_index(1), Ok((&"b", &2)));
+
+        let error = hashvec.try_get_index(2).unwrap_err();
+        assert_eq!(error, IndexOutOfBounds { index: 2, len: 2 });
+        assert_eq!(error.to_string(), "index 2 is out of bounds for a hashvec of length 2");
+    }
+
+    type GrowEvents = Arc<std::sync::Mutex<Vec<(usize, usize)>>>;
+
+    // Returns a grow callback which records every capacity change it's notified of
+    fn recording_callback() -> (GrowCallback, GrowEvents) {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&events);
+        let callback: GrowCallback = Box::new(move |old, new| recorder.lock().unwrap().push((old, new)));
+        (callback, events)
+    }
+
+    #[test]
+    fn collect_reserves_exact_size_sources_up_front() {
+        let source: Vec<(u32, u32)> = (0..1000).map(|i| (i, i)).collect();
+
+        let collected: HashVec<u32, u32> = source.clone().into_iter().collect();
+        assert!(collected.capacity() >= 1000);
+        collected.assert_consistent();
+
+        // At most one allocation is made, and it's already big enough for everything
+        let mut extended: HashVec<u32, u32> = HashVec::new();
+        let (callback, events) = recording_callback();
+        extended.set_grow_callback(callback);
+        extended.extend(source);
+        let events = events.lock().unwrap();
+        assert!(events.len() <= 1, "entries reallocated mid-collect: {:?}", events);
+        assert!(events.iter().all(|(_, new)| *new >= 1000));
+        assert!(extended.capacity() >= 1000);
+    }
+
+    #[test]
+    fn retain_top_n_keeps_greatest_in_stored_order() {
+        let values = [4, 9, 1, 7, 3, 8, 2, 6, 0, 5];
+        let mut hashvec: HashVec<usize, i32> = values.iter().copied().enumerate().collect();
+        hashvec.retain_top_n(3, |a, b| a.1.cmp(&b.1));
+
+        assert!(hashvec.entries_eq(&[(1, 9), (3, 7), (5, 8)]));
+        assert_eq!(hashvec.index(&5), Some(2));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn retain_top_n_edge_counts() {
+        let mut hashvec: HashVec<i32, i32> = (0..4).map(|i| (i, i)).collect();
+        hashvec.retain_top_n(10, |a, b| a.1.cmp(&b.1));
+        assert_eq!(hashvec.len(), 4);
+
+        hashvec.retain_top_n(0, |a, b| a.1.cmp(&b.1));
+        assert!(hashvec.is_empty());
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn fresh_hashvec_is_consistent() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        assert_eq!(hashvec.index_map_len(), 3);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "index map tracks 2 keys, but there are 3 entries")]
+    fn assert_consistent_catches_index_drift() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.order.remove(&calculate_hash(&"b"));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn or_insert_with_key_derives_default_from_key() {
+        let mut hashvec: HashVec<&str, usize> = HashVec::new();
+        *hashvec.entry("apple").or_insert_with_key(|k| k.len()) += 1;
+        *hashvec.entry("fig").or_insert_with_key(|k| k.len()) += 1;
+        *hashvec.entry("apple").or_insert_with_key(|_| unreachable!()) += 1;
+
+        assert!(hashvec.entries_eq(&[("apple", 7), ("fig", 4)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn entry_counts_words() {
+        let mut counts: HashVec<&str, usize> = HashVec::new();
+        for word in "the cat saw the other cat by the door".split(' ') {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.get(&"the"), Some(&3));
+        assert_eq!(counts.get(&"cat"), Some(&2));
+        assert_eq!(counts[2], ("saw", 1));
+        assert_eq!(counts.len(), 6);
+    }
+
+    #[test]
+    fn shrink_to_respects_each_structures_length() {
+        let mut hashvec: HashVec<u32, u32> = (0..10).map(|i| (i, i)).collect();
+
+        // Give the index map far more slack than the entries
+        hashvec.order.reserve(1000);
+        hashvec.shrink_to(0);
+        assert!(hashvec.entries.capacity() >= 10);
+        assert!(hashvec.order.capacity() >= 10);
+        hashvec.assert_consistent();
+
+        // Then give the entries far more slack than the index map
+        hashvec.entries.reserve(1000);
+        hashvec.shrink_to(4);
+        assert!(hashvec.entries.capacity() >= 10);
+        assert!(hashvec.order.capacity() >= 10);
+        hashvec.assert_consistent();
+        assert_eq!(hashvec.get(&7), Some(&7));
+    }
+
+    #[test]
+    fn append_strict_moves_disjoint_entries() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        let mut other: HashVec<&str, i32> = hashvec![("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.append_strict(&mut other), Ok(()));
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+        assert!(other.is_empty());
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn append_strict_reports_conflicts_without_overwriting() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+        let mut other: HashVec<&str, i32> = hashvec![("c", 30), ("b", 20), ("a", 10)];
+
+        assert_eq!(hashvec.append_strict(&mut other), Err(vec!["b", "a"]));
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+        assert!(other.entries_eq(&[("c", 30), ("b", 20), ("a", 10)]));
+    }
+
+    #[test]
+    fn as_unordered_borrows_every_entry() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        let unordered = hashvec.as_unordered();
+
+        assert_eq!(unordered.len(), 3);
+        for (k, v) in hashvec.iter() {
+            assert_eq!(unordered.get(k), Some(&v));
+        }
+    }
+
+    #[test]
+    fn replace_at_swaps_in_new_key_and_value() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.replace_at(1, "z", 26), Some(("b", 2)));
+        assert!(hashvec.entries_eq(&[("a", 1), ("z", 26), ("c", 3)]));
+        assert!(!hashvec.contains_key(&"b"));
+        assert_eq!(hashvec.index(&"z"), Some(1));
+        hashvec.assert_consistent();
+
+        // Reusing the same key at the same index is a plain value replacement
+        assert_eq!(hashvec.replace_at(1, "z", 0), Some(("z", 26)));
+        assert_eq!(hashvec.replace_at(3, "y", 0), None);
+    }
+
+    #[test]
+    fn replace_at_rejects_colliding_keys() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.replace_at(0, "c", 30), None);
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn iter_mut_mutates_in_reverse() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 0), ("b", 0), ("c", 0)];
+
+        let mut iter = hashvec.iter_mut().rev();
+        assert_eq!(iter.len(), 3);
+        for (i, (_, v)) in (&mut iter).enumerate() {
+            *v = i as i32;
+        }
+        assert_eq!(iter.next(), None);
+
+        assert!(hashvec.entries_eq(&[("a", 2), ("b", 1), ("c", 0)]));
+    }
+
+    #[test]
+    fn get_or_try_insert_with_inserts_on_success() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        let value = hashvec.get_or_try_insert_with("b", || "2".parse::<i32>()).unwrap();
+        *value += 1;
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 3)]));
+    }
+
+    #[test]
+    fn get_or_try_insert_with_returns_existing_value() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        let value = hashvec.get_or_try_insert_with("a", || -> Result<i32, ()> { unreachable!() });
+
+        assert_eq!(value, Ok(&mut 1));
+        assert_eq!(hashvec.len(), 1);
+    }
+
+    #[test]
+    fn get_or_try_insert_with_propagates_errors() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+
+        assert!(hashvec.get_or_try_insert_with("b", || "two".parse::<i32>()).is_err());
+        assert!(!hashvec.contains_key(&"b"));
+        assert!(hashvec.entries_eq(&[("a", 1)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn swap_indices_report_returns_swapped_keys() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.swap_indices_report(0, 2), Some((&"a", &"c")));
+        assert!(hashvec.entries_eq(&[("c", 3), ("b", 2), ("a", 1)]));
+        assert_eq!(hashvec.swap_indices_report(0, 3), None);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn memory_usage_grows_with_entries() {
+        let mut hashvec: HashVec<u64, u64> = HashVec::new();
+        let empty = hashvec.memory_usage();
+
+        for i in 0..1000 {
+            hashvec.insert(i, i);
+        }
+        assert!(hashvec.memory_usage() > empty);
+        assert!(hashvec.memory_usage() >= 1000 * std::mem::size_of::<(u64, u64)>());
+    }
+
+    #[test]
+    fn evict_returns_removed_keys_in_order() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 5), ("b", 50), ("c", 7), ("d", 70), ("e", 1)];
+        let evicted = hashvec.evict(|_, v| *v > 10);
+
+        assert_eq!(evicted, vec!["b", "d"]);
+        assert!(hashvec.entries_eq(&[("a", 5), ("c", 7), ("e", 1)]));
+        assert_eq!(hashvec.index(&"e"), Some(2));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn reserve_zero_is_a_no_op() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::with_capacity(0);
+        hashvec.reserve(0);
+        assert_eq!(hashvec.entries.capacity(), 0);
+        assert_eq!(hashvec.order.capacity(), 0);
+    }
+
+    #[test]
+    fn try_with_capacity_succeeds_for_normal_sizes() {
+        let hashvec: HashVec<u32, u32> = HashVec::try_with_capacity(64).unwrap();
+        assert!(hashvec.capacity() >= 64);
+        assert!(hashvec.is_empty());
+    }
+
+    #[test]
+    fn try_with_capacity_fails_gracefully_on_overflow() {
+        assert!(HashVec::<u64, u64>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn flip_collapses_repeated_values() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 1), ("d", 3)];
+        let flipped = hashvec.flip();
+
+        // The repeated value keeps its first position but takes its last key
+        assert!(flipped.entries_eq(&[(1, "c"), (2, "b"), (3, "d")]));
+        flipped.assert_consistent();
+    }
+
+    #[test]
+    fn zip_values_joins_on_shared_keys() {
+        let names: HashVec<u32, &str> = hashvec![(1, "one"), (2, "two"), (3, "three"), (4, "four")];
+        let colors: HashVec<u32, char> = hashvec![(4, 'g'), (2, 'r'), (5, 'b')];
+        let zipped = names.zip_values(&colors);
+
+        assert!(zipped.entries_eq(&[(2, (&"two", &'r')), (4, (&"four", &'g'))]));
+        zipped.assert_consistent();
+    }
+
+    #[test]
+    fn pop_n_removes_from_the_back_in_pop_order() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)];
+
+        assert_eq!(hashvec.pop_n(3), vec![("e", 5), ("d", 4), ("c", 3)]);
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+        hashvec.assert_consistent();
+
+        assert_eq!(hashvec.pop_n(10), vec![("b", 2), ("a", 1)]);
+        assert!(hashvec.is_empty());
+    }
+
+    #[test]
+    fn contains_key_at_checks_key_and_index() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+
+        assert!(hashvec.contains_key_at(&"b", 1));
+        assert!(!hashvec.contains_key_at(&"b", 0));
+        assert!(!hashvec.contains_key_at(&"z", 0));
+    }
+
+    #[test]
+    fn keys_sorted_leaves_order_untouched() {
+        let hashvec: HashVec<&str, i32> = hashvec![("c", 1), ("a", 2), ("b", 3)];
+
+        assert_eq!(hashvec.keys_sorted(), vec![&"a", &"b", &"c"]);
+        assert_eq!(hashvec.index(&"c"), Some(0));
+        assert_eq!(hashvec.index(&"a"), Some(1));
+        assert_eq!(hashvec.index(&"b"), Some(2));
+    }
+
+    #[test]
+    fn values_sorted_leaves_order_untouched() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 3), ("b", 1), ("c", 2)];
+
+        assert_eq!(hashvec.values_sorted(), vec![&1, &2, &3]);
+        assert!(hashvec.entries_eq(&[("a", 3), ("b", 1), ("c", 2)]));
+    }
+
+    #[test]
+    fn occupied_entry_move_to_front() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        match hashvec.entry("c") {
+            Entry::Occupied(entry) => {
+                let mut moved = entry.move_to(0);
+                assert_eq!(moved.index(), 0);
+                *moved.get_mut() += 10;
+            },
+            Entry::Vacant(_) => panic!("expected an occupied entry")
+        }
+
+        assert!(hashvec.entries_eq(&[("c", 13), ("a", 1), ("b", 2)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 is out of bounds")]
+    fn occupied_entry_move_to_out_of_bounds_panics() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        if let Some(entry) = hashvec.get_mut_entry(&"a") {
+            entry.move_to(3);
+        }
+    }
+
+    #[test]
+    fn replace_all_swaps_out_every_entry() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.replace_all(vec![("z", 26), ("b", 20), ("y", 25), ("z", 0)]);
+
+        // The repeated key keeps its first position but takes its last value
+        assert!(hashvec.entries_eq(&[("z", 0), ("b", 20), ("y", 25)]));
+        assert!(!hashvec.contains_key(&"a"));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn cursor_navigates_and_clamps() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        let mut cursor = hashvec.cursor();
+        assert_eq!(cursor.current(), Some((&"a", &1)));
+
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some((&"a", &1)));
+
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some((&"c", &3)));
+
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some((&"b", &2)));
+    }
+
+    #[test]
+    fn cursor_mut_edits_current_value() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        let mut cursor = hashvec.cursor_mut();
+        cursor.move_next();
+        if let Some((_, v)) = cursor.current_mut() {
+            *v *= 10;
+        }
+        assert_eq!(cursor.current(), Some((&"b", &20)));
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 20), ("c", 3)]));
+    }
+
+    #[test]
+    fn cursor_over_empty_hashvec() {
+        let hashvec: HashVec<&str, i32> = HashVec::new();
+        let mut cursor = hashvec.cursor();
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn into_iterator_for_mut_ref_mutates_values() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        for (k, v) in &mut hashvec {
+            if *k != "b" {
+                *v *= 100;
+            }
+        }
+
+        assert!(hashvec.entries_eq(&[("a", 100), ("b", 2), ("c", 300)]));
+    }
+
+    #[test]
+    fn sort_and_dedup_keeps_last_value_per_key() {
+        let mut hashvec = HashVec::from_entries(vec![("c", 1), ("a", 2), ("c", 3), ("b", 4), ("a", 5)]);
+        hashvec.sort_and_dedup();
+
+        assert!(hashvec.entries_eq(&[("a", 5), ("b", 4), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn grow_callback_fires_on_insertion_growth() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::new();
+        let (callback, events) = recording_callback();
+        hashvec.set_grow_callback(callback);
+
+        for i in 0..100 {
+            hashvec.insert(i, i);
+        }
+
+        let events = events.lock().unwrap();
+        assert!(!events.is_empty());
+        assert_eq!(events[0].0, 0);
+        assert!(events.windows(2).all(|pair| pair[0].1 == pair[1].0));
+        assert_eq!(events.last().unwrap().1, hashvec.entries.capacity());
+    }
+
+    #[test]
+    fn grow_callback_stays_quiet_within_capacity() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::with_capacity(16);
+        let (callback, events) = recording_callback();
+        hashvec.set_grow_callback(callback);
+
+        for i in 0..16 {
+            hashvec.insert(i, i);
+        }
+        hashvec.reserve(0);
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn grow_callback_fires_on_bulk_growth() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::new();
+        let (callback, events) = recording_callback();
+        hashvec.set_grow_callback(callback);
+
+        hashvec.extend((0..100).map(|i| (i, i)));
+        assert_eq!(events.lock().unwrap().len(), 1);
+
+        let capacity = hashvec.entries.capacity();
+        hashvec.bulk_update((0..1600).map(|i| (i, i)));
+        assert_eq!(events.lock().unwrap().len(), 2);
+        assert_eq!(events.lock().unwrap()[1].0, capacity);
+
+        hashvec.reserve(hashvec.entries.capacity());
+        assert_eq!(events.lock().unwrap().len(), 3);
+        assert_eq!(events.lock().unwrap()[2].1, hashvec.entries.capacity());
+    }
+
+    #[test]
+    fn get_indices_follows_requested_order() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.get_indices(&[2, 0, 99]), vec![Some((&"c", &3)), Some((&"a", &1)), None]);
+        assert!(hashvec.get_indices(&[]).is_empty());
+    }
+
+    #[test]
+    fn compact_drops_tombstones() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+        for (k, v) in hashvec.iter_mut() {
+            if *k == "a" || *k == "c" {
+                *v = -1;
+            }
+        }
+        hashvec.compact(|v| *v == -1);
+
+        assert!(hashvec.entries_eq(&[("b", 2), ("d", 4)]));
+        assert_eq!(hashvec.index(&"d"), Some(1));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn position_by_value_finds_earliest_match() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 1)];
+
+        assert_eq!(hashvec.position_by_value(&1), Some(0));
+        assert_eq!(hashvec.position_by_value(&2), Some(1));
+        assert_eq!(hashvec.position_by_value(&3), None);
+    }
+
+    #[test]
+    fn vacant_entry_insert_at_front() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+
+        match hashvec.entry("z") {
+            Entry::Vacant(entry) => *entry.insert_at(0, 26) += 1,
+            Entry::Occupied(_) => panic!("expected a vacant entry")
+        }
+
+        assert!(hashvec.entries_eq(&[("z", 27), ("a", 1), ("b", 2)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 is out of bounds")]
+    fn vacant_entry_insert_at_out_of_bounds_panics() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+        if let Entry::Vacant(entry) = hashvec.entry("z") {
+            entry.insert_at(3, 26);
+        }
+    }
+
+    #[test]
+    fn key_order_captures_swaps() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        assert_eq!(hashvec.key_order(), vec![&"a", &"b", &"c"]);
+
+        hashvec.swap_indices(0, 2);
+        assert_eq!(hashvec.key_order(), vec![&"c", &"b", &"a"]);
+    }
+
+    thread_local! {
+        static HASH_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        static COMPARISON_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    // A key which counts every time it's hashed or compared (per test thread), for checking how much work an operation does
+    #[derive(Debug, Clone, Copy)]
+    struct Counted(u32);
+
+    impl Hash for Counted {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            HASH_COUNT.with(|count| count.set(count.get() + 1));
+            self.0.hash(state);
+        }
+    }
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Counted) -> bool {
+            COMPARISON_COUNT.with(|count| count.set(count.get() + 1));
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Counted {}
+
+    // Runs `f` and returns its result along with the number of times a `Counted` key was hashed while it ran
+    fn count_hashes<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = HASH_COUNT.with(|count| count.get());
+        let result = f();
+        (result, HASH_COUNT.with(|count| count.get()) - before)
+    }
+
+    // Runs `f` and returns its result along with the number of times a `Counted` key was compared while it ran
+    fn count_comparisons<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = COMPARISON_COUNT.with(|count| count.get());
+        let result = f();
+        (result, COMPARISON_COUNT.with(|count| count.get()) - before)
+    }
+
+    #[test]
+    fn removing_last_key_leaves_other_indices_alone() {
+        let mut hashvec: HashVec<Counted, i32> = (0..4).map(|i| (Counted(i), i as i32)).collect();
+        let mut expected = hashvec.order.clone();
+        expected.remove(&calculate_hash(&Counted(3)));
+
+        // Only the removed key itself is hashed, so none of the other entries are re-indexed
+        let (removed, hashes) = count_hashes(|| hashvec.remove_key_entry(&Counted(3)));
+        assert_eq!(removed, Some((Counted(3), 3)));
+        assert_eq!(hashes, 1);
+        assert_eq!(hashvec.order, expected);
+        hashvec.assert_consistent();
+
+        // Removing from the middle, by contrast, re-hashes every key which followed it
+        let (_, hashes) = count_hashes(|| hashvec.remove_key_entry(&Counted(0)));
+        assert_eq!(hashes, 3);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn removing_middle_key_reindexes_followers() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+
+        assert_eq!(hashvec.remove_key(&"b"), Some(2));
+        assert_eq!(hashvec.index(&"a"), Some(0));
+        assert_eq!(hashvec.index(&"c"), Some(1));
+        assert_eq!(hashvec.index(&"d"), Some(2));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn values_mut_mutates_every_value() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        let values = hashvec.values_mut();
+        assert_eq!(values.len(), 3);
+        for v in values {
+            *v *= 2;
+        }
+        if let Some(v) = hashvec.values_mut().next_back() {
+            *v += 1;
+        }
+
+        assert!(hashvec.entries_eq(&[("a", 2), ("b", 4), ("c", 7)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn try_from_accepts_unique_keys() {
+        let hashvec = HashVec::try_from(vec![("a", 1), ("b", 2)]).unwrap();
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn try_from_lists_duplicate_keys() {
+        let error = HashVec::try_from(vec![("a", 1), ("b", 2), ("a", 3)]).unwrap_err();
+        assert_eq!(error, DuplicateKeys { keys: vec!["a"] });
+        assert_eq!(error.to_string(), "found 1 duplicate key(s) while building hashvec");
+    }
+
+    #[test]
+    fn max_len_caps_insert_and_push() {
+        let mut hashvec: HashVec<&str, i32> = HashVec::new();
+        hashvec.set_max_len(Some(2));
+        hashvec.insert("a", 1);
+        assert!(!hashvec.is_full());
+        hashvec.push(("b", 2));
+        assert!(hashvec.is_full());
+
+        // New keys are refused, but existing ones can still be overwritten
+        hashvec.insert("c", 3);
+        hashvec.push(("d", 4));
+        hashvec.insert("a", 10);
+        hashvec.push(("a", 100));
+        assert!(hashvec.entries_eq(&[("b", 2), ("a", 100)]));
+        hashvec.assert_consistent();
+
+        hashvec.set_max_len(None);
+        hashvec.insert("c", 3);
+        assert_eq!(hashvec.len(), 3);
+    }
+
+    #[test]
+    fn max_len_below_len_still_allows_overwrites() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.set_max_len(Some(1));
+
+        hashvec.push(("a", 10));
+        hashvec.insert("b", 20);
+        assert!(hashvec.entries_eq(&[("b", 20), ("c", 3), ("a", 10)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn max_len_caps_every_insertion_path() {
+        let mut hashvec: HashVec<&str, i32> = HashVec::new();
+        hashvec.set_max_len(Some(2));
+
+        hashvec.replace_all(vec![("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+
+        hashvec.update_or_insert("c", |v| *v += 1, 3);
+        hashvec.update_or_insert("a", |v| *v += 1, 0);
+        assert!(hashvec.entries_eq(&[("a", 2), ("b", 2)]));
+
+        hashvec.bulk_update(vec![("c", 3), ("b", 20)]);
+        assert!(hashvec.entries_eq(&[("a", 2), ("b", 20)]));
+
+        assert_eq!(hashvec.checked_insert_at(0, "c", 3), Err(("c", 3)));
+        match hashvec.entry("c") {
+            Entry::Vacant(entry) => assert_eq!(entry.try_insert(3), Err(("c", 3))),
+            Entry::Occupied(_) => panic!("expected a vacant entry")
+        }
+        *hashvec.entry("a").or_insert(0) += 1;
+
+        assert!(hashvec.entries_eq(&[("a", 3), ("b", 20)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot insert a new key into a full hashvec")]
+    fn max_len_makes_or_insert_panic() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        hashvec.set_max_len(Some(1));
+        hashvec.entry("b").or_insert(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot insert a new key into a full hashvec")]
+    fn max_len_makes_get_or_insert_default_panic() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        hashvec.set_max_len(Some(1));
+        hashvec.get_or_insert_default("b");
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot insert a new key into a full hashvec")]
+    fn max_len_makes_vacant_insert_at_panic() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        hashvec.set_max_len(Some(1));
+        if let Entry::Vacant(entry) = hashvec.entry("b") {
+            entry.insert_at(0, 2);
+        }
+    }
+
+    #[test]
+    fn rotate_to_key_makes_key_the_front() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)];
+
+        assert!(hashvec.rotate_to_key(&"c"));
+        assert_eq!(hashvec.key_order(), vec![&"c", &"d", &"e", &"a", &"b"]);
+        for (i, k) in ["c", "d", "e", "a", "b"].iter().enumerate() {
+            assert_eq!(hashvec.index(k), Some(i));
+        }
+        hashvec.assert_consistent();
+
+        assert!(!hashvec.rotate_to_key(&"z"));
+        assert_eq!(hashvec.key_order(), vec![&"c", &"d", &"e", &"a", &"b"]);
+    }
+
+    #[test]
+    fn checked_swap_indices_swaps_in_range() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.checked_swap_indices(0, 2), Ok(()));
+        assert!(hashvec.entries_eq(&[("c", 3), ("b", 2), ("a", 1)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn checked_swap_indices_reports_out_of_range() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.checked_swap_indices(3, 0), Err((3, 0)));
+        assert_eq!(hashvec.checked_swap_indices(0, 3), Err((0, 3)));
+        assert_eq!(hashvec.checked_swap_indices(5, 7), Err((5, 7)));
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+    }
+
+    #[test]
+    fn drain_range_yields_and_reindexes() {
+        let mut hashvec: HashVec<i32, i32> = (0..5).map(|i| (i, i * 10)).collect();
+
+        let drained: Vec<(i32, i32)> = hashvec.drain_range(1..3).collect();
+        assert_eq!(drained, vec![(1, 10), (2, 20)]);
+        assert!(hashvec.entries_eq(&[(0, 0), (3, 30), (4, 40)]));
+        assert_eq!(hashvec.index(&4), Some(2));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn drain_range_dropped_early_removes_whole_range() {
+        let mut hashvec: HashVec<i32, i32> = (0..6).map(|i| (i, i)).collect();
+
+        let mut drain = hashvec.drain_range(1..=3);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next(), Some((1, 1)));
+        assert_eq!(drain.next_back(), Some((3, 3)));
+        drop(drain);
+
+        assert!(hashvec.entries_eq(&[(0, 0), (4, 4), (5, 5)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn drain_range_leaked_leaves_hashvec_consistent() {
+        let mut hashvec: HashVec<i32, i32> = (0..5).map(|i| (i, i)).collect();
+
+        std::mem::forget(hashvec.drain_range(1..2));
+        assert_eq!(hashvec.len(), 1);
+        assert_eq!(hashvec.index_map_len(), 1);
+        assert_eq!(hashvec.get(&4), None);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "drain range end 18446744073709551615 is out of bounds")]
+    fn drain_range_inclusive_max_panics() {
+        let mut hashvec: HashVec<i32, i32> = (0..5).map(|i| (i, i)).collect();
+        hashvec.drain_range(..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "drain range end 6 is out of bounds")]
+    fn drain_range_past_end_panics() {
+        let mut hashvec: HashVec<i32, i32> = (0..5).map(|i| (i, i)).collect();
+        hashvec.drain_range(2..6);
+    }
+
+    #[test]
+    fn evict_oldest_removes_from_the_front() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)];
+
+        assert_eq!(hashvec.evict_oldest(2), vec![("a", 1), ("b", 2)]);
+        assert!(hashvec.entries_eq(&[("c", 3), ("d", 4), ("e", 5)]));
+        assert_eq!(hashvec.index(&"c"), Some(0));
+        hashvec.assert_consistent();
+
+        assert_eq!(hashvec.evict_oldest(10).len(), 3);
+        assert!(hashvec.is_empty());
+    }
+
+    #[test]
+    fn touch_moves_key_to_the_back() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+
+        assert!(hashvec.touch(&"b"));
+        assert!(hashvec.entries_eq(&[("a", 1), ("c", 3), ("d", 4), ("b", 2)]));
+        assert_eq!(hashvec.index(&"c"), Some(1));
+        assert_eq!(hashvec.index(&"b"), Some(3));
+        hashvec.assert_consistent();
+
+        assert!(!hashvec.touch(&"z"));
+    }
+
+    #[test]
+    fn get_and_touch_reads_after_moving() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.get_and_touch(&"a"), Some(&1));
+        assert_eq!(hashvec.last(), Some((&"a", &1)));
+        assert_eq!(hashvec.get_and_touch(&"z"), None);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn extend_from_borrowed_pairs() {
+        let mut hashvec: HashVec<String, i32> = HashVec::new();
+        hashvec.insert("a".to_string(), 1);
+        hashvec.insert("b".to_string(), 2);
+        let mut other: HashVec<String, i32> = HashVec::new();
+        other.insert("b".to_string(), 20);
+        other.insert("c".to_string(), 30);
+
+        hashvec.extend(other.iter());
+
+        // Overlapping keys are overwritten and pushed to the back, and the source is untouched
+        let pairs: Vec<(&str, i32)> = hashvec.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        assert_eq!(pairs, vec![("a", 1), ("b", 20), ("c", 30)]);
+        assert_eq!(other.len(), 2);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn retain_with_mapping_reports_old_indices() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)];
+        let mapping = hashvec.retain_with_mapping(|_, v| v % 2 == 1);
+
+        assert_eq!(mapping, vec![0, 2, 4]);
+        assert!(hashvec.entries_eq(&[("a", 1), ("c", 3), ("e", 5)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn split_first_and_last_on_empty() {
+        let hashvec: HashVec<&str, i32> = HashVec::new();
+        assert!(hashvec.split_first().is_none());
+        assert!(hashvec.split_last().is_none());
+    }
+
+    #[test]
+    fn split_first_and_last_on_single_entry() {
+        let hashvec = HashVec::singleton("a", 1);
+        assert_eq!(hashvec.split_first(), Some(((&"a", &1), &[][..])));
+        assert_eq!(hashvec.split_last(), Some((&[][..], (&"a", &1))));
+    }
+
+    #[test]
+    fn split_first_and_last_on_many_entries() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        assert_eq!(hashvec.split_first(), Some(((&"a", &1), &[("b", 2), ("c", 3)][..])));
+        assert_eq!(hashvec.split_last(), Some((&[("a", 1), ("b", 2)][..], (&"c", &3))));
+    }
+
+    #[test]
+    fn to_arc_slice_matches_stored_order() {
+        let hashvec: HashVec<&str, i32> = hashvec![("b", 2), ("a", 1)];
+        let snapshot = hashvec.to_arc_slice();
+
+        let shared = Arc::clone(&snapshot);
+        let handle = std::thread::spawn(move || shared.iter().map(|(_, v)| *v).sum::<i32>());
+        assert_eq!(handle.join().unwrap(), 3);
+        assert_eq!(&snapshot[..], &[("b", 2), ("a", 1)]);
+    }
+
+    #[test]
+    fn entry_resolves_its_lookup_once() {
+        let mut hashvec: HashVec<Counted, u32> = (0..3).map(|i| (Counted(i), i)).collect();
+
+        // Each entry() call hashes its key once, no matter how long the chain that follows is
+        for chain_len in 0..4 {
+            for key in [Counted(1), Counted(10 + chain_len)] {
+                let (_, hashes) = count_hashes(|| {
+                    let mut entry = hashvec.entry(key);
+                    for _ in 0..chain_len {
+                        entry = entry.and_modify(|v| *v += 1);
+                    }
+                    *entry.or_insert(0) += 1;
+                });
+                assert_eq!(hashes, 1, "{:?} with {} and_modify calls", key, chain_len);
+            }
+        }
+
+        let (_, hashes) = count_hashes(|| *hashvec.entry(Counted(1)).and_modify(|v| *v *= 2).or_insert_with(|| 0) += 1);
+        assert_eq!(hashes, 1);
+
+        // 1 + (0 + 1 + 2 + 3) from the chains plus 4 or_insert additions, then doubled and incremented
+        assert_eq!(hashvec.get(&Counted(1)), Some(&23));
+        assert_eq!(hashvec.get(&Counted(13)), Some(&1));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn equal_range_by_key_finds_repeated_values() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 2), ("d", 2), ("e", 5)];
+
+        assert_eq!(hashvec.equal_range_by_key(&2, |_, v| *v), 1..4);
+        assert_eq!(hashvec.equal_range_by_key(&5, |_, v| *v), 4..5);
+        assert_eq!(hashvec.equal_range_by_key(&3, |_, v| *v), 4..4);
+        assert_eq!(hashvec.equal_range_by_key(&0, |_, v| *v), 0..0);
+    }
+
+    #[test]
+    fn parts_round_trip() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        let (entries, order) = hashvec.into_parts();
+        assert_eq!(order.len(), 3);
+        assert_eq!(order.get(&calculate_hash(&"c")), Some(&2));
+
+        let rebuilt = HashVec::from_entries(entries.clone());
+        assert!(rebuilt.entries_eq(&entries));
+        assert_eq!(rebuilt.index(&"b"), Some(1));
+        rebuilt.assert_consistent();
+
+        let reassembled = unsafe { HashVec::from_parts(entries, order) };
+        assert_eq!(reassembled.get(&"c"), Some(&3));
+        reassembled.assert_consistent();
+    }
+
+    #[test]
+    fn try_retain_completes_without_errors() {
+        let mut hashvec: HashVec<i32, i32> = (0..6).map(|i| (i, i)).collect();
+
+        assert_eq!(hashvec.try_retain(|_, v| Ok::<bool, ()>(v % 2 == 0)), Ok(()));
+        assert!(hashvec.entries_eq(&[(0, 0), (2, 2), (4, 4)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn try_retain_stops_at_first_error() {
+        let mut hashvec: HashVec<i32, i32> = (0..6).map(|i| (i, i)).collect();
+        let result = hashvec.try_retain(|k, v| {
+            if *k == 3 {
+                Err("bad entry")
+            } else {
+                Ok(v % 2 == 0)
+            }
+        });
+
+        // Entries before the error are processed, while it and everything after it are kept
+        assert_eq!(result, Err("bad entry"));
+        assert!(hashvec.entries_eq(&[(0, 0), (2, 2), (3, 3), (4, 4), (5, 5)]));
+        assert_eq!(hashvec.index(&5), Some(4));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn from_entries_ordered_applies_permutation() {
+        let entries = vec![("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+        let hashvec = HashVec::from_entries_ordered(entries, &[2, 0, 3, 1]);
+
+        assert!(hashvec.entries_eq(&[("c", 3), ("a", 1), ("d", 4), ("b", 2)]));
+        assert_eq!(hashvec.index(&"b"), Some(3));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "order is not a permutation")]
+    fn from_entries_ordered_rejects_repeats() {
+        HashVec::from_entries_ordered(vec![("a", 1), ("b", 2)], &[0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "order has 1 positions, but there are 2 entries")]
+    fn from_entries_ordered_rejects_wrong_length() {
+        HashVec::from_entries_ordered(vec![("a", 1), ("b", 2)], &[0]);
+    }
+
+    #[test]
+    fn get_mut_entry_mutates_then_moves() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        let mut entry = hashvec.get_mut_entry(&"b").unwrap();
+        assert_eq!(entry.index(), 1);
+        *entry.get_mut() = 20;
+        let entry = entry.move_to(0);
+        assert_eq!(entry.index(), 0);
+
+        assert!(hashvec.entries_eq(&[("b", 20), ("a", 1), ("c", 3)]));
+        hashvec.assert_consistent();
+
+        assert_eq!(hashvec.get_mut_entry(&"c").unwrap().remove(), 3);
+        assert!(hashvec.get_mut_entry(&"c").is_none());
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn dedup_keys_keep_first_drops_later_duplicates() {
+        let mut hashvec = HashVec::from_entries(vec![("a", 1), ("b", 2), ("a", 3), ("c", 4), ("b", 5)]);
+        hashvec.dedup_keys_keep_first();
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 4)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn index_map_grows_in_lockstep_with_entries() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::new();
+
+        for i in 0..1000 {
+            let entries_capacity = hashvec.entries.capacity();
+            hashvec.insert(i, i);
+            if hashvec.entries.capacity() != entries_capacity {
+                // Whenever the entries grow, the index map is grown to match at the same time
+                assert!(hashvec.order.capacity() >= hashvec.entries.capacity());
+            }
+        }
+
+        for i in 1000..2000 {
+            hashvec.push((i, i));
+            assert!(hashvec.order.capacity() >= hashvec.len());
+        }
+    }
+
+    #[test]
+    fn rposition_finds_last_match() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 1), ("d", 2)];
+
+        assert_eq!(hashvec.rposition(|_, v| *v == 1), Some(2));
+        assert_eq!(hashvec.rposition(|_, v| *v == 2), Some(3));
+        assert_eq!(hashvec.rposition(|_, v| *v == 3), None);
+    }
+
+    #[test]
+    fn shrink_to_fit_trims_entries_exactly() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::with_capacity(1000);
+        for i in 0..3 {
+            hashvec.insert(i, i);
+        }
+        hashvec.shrink_to_fit();
+
+        assert_eq!(hashvec.entries.capacity(), 3);
+        assert!(hashvec.order.capacity() >= 3);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn vacant_entry_into_key_recovers_key() {
+        let mut hashvec: HashVec<String, i32> = HashVec::new();
+        hashvec.insert("a".to_string(), 1);
+
+        match hashvec.entry("b".to_string()) {
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), "b"),
+            Entry::Occupied(_) => panic!("expected a vacant entry")
+        }
+        assert_eq!(hashvec.len(), 1);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn position_max_and_min_value_prefer_first_on_ties() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 3), ("b", 7), ("c", 1), ("d", 7), ("e", 1)];
+
+        assert_eq!(hashvec.position_max_value(), Some(1));
+        assert_eq!(hashvec.position_min_value(), Some(2));
+    }
+
+    #[test]
+    fn position_max_and_min_value_on_empty() {
+        let hashvec: HashVec<&str, i32> = HashVec::new();
+
+        assert_eq!(hashvec.position_max_value(), None);
+        assert_eq!(hashvec.position_min_value(), None);
+    }
+
+    #[test]
+    fn bulk_update_matches_per_item_pushes() {
+        let batch: Vec<(u32, u32)> = (0..50).map(|i| ((i * 7) % 30, i)).collect();
+        let mut bulk: HashVec<u32, u32> = (0..20).map(|i| (i, 0)).collect();
+        let mut looped = bulk.clone();
+
+        bulk.bulk_update(batch.clone());
+        for entry in batch {
+            looped.push(entry);
+        }
+
+        assert!(bulk.entries_eq(&looped.entries));
+        bulk.assert_consistent();
+
+        // With a maximum length, the same new keys have to be refused as when pushing one at a time
+        let batches: [Vec<(&str, i32)>; 3] = [
+            vec![("a", 1), ("b", 2), ("a", 3)],
+            vec![("x", 1), ("c", 2), ("d", 3), ("c", 4), ("x", 5), ("e", 6)],
+            vec![("y", 1), ("y", 2), ("z", 3)]
+        ];
+
+        for max_len in 0..6 {
+            for batch in &batches {
+                let mut bulk: HashVec<&str, i32> = hashvec![("x", 0), ("y", 0)];
+                bulk.set_max_len(Some(max_len));
+                let mut looped = bulk.clone();
+
+                bulk.bulk_update(batch.clone());
+                for &entry in batch {
+                    looped.push(entry);
+                }
+
+                assert!(bulk.entries_eq(&looped.entries), "max_len {}: {:?} != {:?}", max_len, bulk.entries, looped.entries);
+                bulk.assert_consistent();
+            }
+        }
+
+        // New keys get in by their first occurrence, and later occurrences still overwrite them
+        let mut hashvec: HashVec<&str, i32> = HashVec::new();
+        hashvec.set_max_len(Some(1));
+        hashvec.bulk_update(vec![("a", 1), ("b", 2), ("a", 3)]);
+        assert!(hashvec.entries_eq(&[("a", 3)]));
+    }
+
+    #[test]
+    fn bulk_update_hashes_less_than_per_item_pushes() {
+        let batch: Vec<(Counted, u32)> = (0..200).map(|i| (Counted((i * 7) % 120), i)).collect();
+        let mut bulk: HashVec<Counted, u32> = (0..100).map(|i| (Counted(i), 0)).collect();
+        let mut looped = bulk.clone();
+
+        let (_, bulk_hashes) = count_hashes(|| bulk.bulk_update(batch.clone()));
+        let (_, looped_hashes) = count_hashes(|| {
+            for entry in batch {
+                looped.push(entry);
+            }
+        });
+        assert!(bulk.entries_eq(&looped.entries));
+
+        // Each batch key is hashed once, and then the index is rebuilt a single time
+        assert_eq!(bulk_hashes, 200 + bulk.len());
+        assert!(bulk_hashes < looped_hashes, "{} >= {}", bulk_hashes, looped_hashes);
+    }
+
+    #[test]
+    fn bulk_update_grows_at_most_once() {
+        let mut hashvec: HashVec<u32, u32> = (0..10).map(|i| (i, i)).collect();
+        let (callback, events) = recording_callback();
+        hashvec.set_grow_callback(callback);
+
+        hashvec.bulk_update((5..500).map(|i| (i, i)));
+        assert_eq!(events.lock().unwrap().len(), 1);
+        assert_eq!(hashvec.len(), 500);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn key_value_aliases_match_first_and_last() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        assert_eq!(hashvec.first_key_value(), hashvec.first());
+        assert_eq!(hashvec.last_key_value(), hashvec.last());
+        assert_eq!(hashvec.last_key_value(), Some((&"c", &3)));
+
+        let empty: HashVec<&str, i32> = HashVec::new();
+        assert_eq!(empty.first_key_value(), None);
+        assert_eq!(empty.last_key_value(), None);
+    }
+
+    #[test]
+    fn push_indexed_returns_final_index() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+
+        assert_eq!(hashvec.push_indexed(("c", 3)), 2);
+        assert_eq!(hashvec.push_indexed(("a", 10)), 2);
+        assert!(hashvec.entries_eq(&[("b", 2), ("c", 3), ("a", 10)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot push a new key onto a full hashvec")]
+    fn push_indexed_panics_when_full() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        hashvec.set_max_len(Some(1));
+        hashvec.push_indexed(("b", 2));
+    }
+
+    #[test]
+    fn subset_keeps_self_order() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+        let subset = hashvec.subset(&["d", "z", "b"]);
+
+        assert!(subset.entries_eq(&[("b", 2), ("d", 4)]));
+        subset.assert_consistent();
+    }
+
+    #[test]
+    fn filtered_matches_manual_filter() {
+        let hashvec: HashVec<i32, i32> = (0..10).map(|i| (i, i * 3)).collect();
+
+        let filtered: Vec<(&i32, &i32)> = hashvec.filtered(|_, v| v % 2 == 0).collect();
+        let manual: Vec<(&i32, &i32)> = hashvec.iter().filter(|(_, v)| *v % 2 == 0).collect();
+        assert_eq!(filtered, manual);
+        assert_eq!(hashvec.len(), 10);
+    }
+
+    #[test]
+    fn rekey_renames_in_place() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        let entry = hashvec.get_mut_entry(&"b").unwrap();
+        let entry = entry.rekey("z").ok().unwrap();
+        assert_eq!(entry.key(), &"z");
+        assert_eq!(entry.index(), 1);
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("z", 2), ("c", 3)]));
+        assert!(!hashvec.contains_key(&"b"));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn rekey_rejects_collisions() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        let entry = hashvec.get_mut_entry(&"a").unwrap();
+        match entry.rekey("c") {
+            Err((key, error)) => {
+                assert_eq!(key, "c");
+                assert_eq!(error, RekeyError { index: 2 });
+                assert_eq!(error.to_string(), "key already belongs to the hashvec entry at index 2");
+            },
+            Ok(_) => panic!("expected the rekey to be rejected")
+        }
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn quarter_full_policy_releases_capacity() {
+        let mut hashvec: HashVec<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+        hashvec.set_shrink_policy(ShrinkPolicy::WhenQuarterFull);
+        let capacity = hashvec.entries.capacity();
+
+        for i in 0..900 {
+            hashvec.remove_key(&i);
+        }
+        while hashvec.len() > 50 {
+            hashvec.pop();
+        }
+
+        assert!(hashvec.entries.capacity() < capacity / 4);
+        assert!(hashvec.entries.capacity() >= hashvec.len());
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn never_policy_keeps_capacity() {
+        let mut hashvec: HashVec<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+        let capacity = hashvec.entries.capacity();
+
+        for i in 0..990 {
+            hashvec.remove_key(&i);
+        }
+        assert_eq!(hashvec.entries.capacity(), capacity);
+    }
+
+    #[test]
+    fn from_keys_with_derives_values() {
+        let hashvec = HashVec::from_keys_with(vec!["kiwi", "fig", "banana", "fig"], |k| k.len());
+
+        assert!(hashvec.entries_eq(&[("kiwi", 4), ("fig", 3), ("banana", 6)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn clone_drops_spare_capacity() {
+        let mut hashvec: HashVec<u32, u32> = HashVec::with_capacity(10000);
+        for i in 0..10 {
+            hashvec.insert(i, i);
+        }
+        hashvec.set_max_len(Some(20));
+        let clone = hashvec.clone();
+
+        assert!(clone.entries.capacity() < 100);
+        assert!(clone.entries_eq(&hashvec.entries));
+        assert!(clone.max_len == Some(20));
+        clone.assert_consistent();
+    }
+
+    #[test]
+    fn push_existing_key_passes_debug_checks() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.push(("a", 10));
+
+        assert_eq!(hashvec.last(), Some((&"a", &10)));
+        assert_eq!(hashvec.len(), 3);
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn push_checks_stay_constant_time() {
+        // The debug checks in push mustn't scan the entries, so pushing does the same amount of key work however big the hashvec is
+        let mut small: HashVec<Counted, u32> = HashVec::new();
+        let mut large: HashVec<Counted, u32> = (0..1000).map(|i| (Counted(i), i)).collect();
+
+        let ((_, small_hashes), small_comparisons) = count_comparisons(|| count_hashes(|| small.push((Counted(5000), 0))));
+        let ((_, large_hashes), large_comparisons) = count_comparisons(|| count_hashes(|| large.push((Counted(5000), 0))));
+        assert_eq!(small_hashes, large_hashes);
+        assert_eq!(small_comparisons, large_comparisons);
+    }
+
+    #[test]
+    fn step_iter_yields_every_nth_entry() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 0), ("b", 1), ("c", 2), ("d", 3), ("e", 4), ("f", 5)];
+
+        let stepped: Vec<(&&str, &i32)> = hashvec.step_iter(2).collect();
+        assert_eq!(stepped, vec![(&"a", &0), (&"c", &2), (&"e", &4)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be greater than zero")]
+    fn step_iter_rejects_zero() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 0)];
+        let _ = hashvec.step_iter(0);
+    }
+
+    #[test]
+    fn max_and_min_value_by_use_comparator() {
+        let hashvec: HashVec<u32, &str> = hashvec![(1, "kiwi"), (2, "banana"), (3, "fig"), (4, "cherry"), (5, "yam")];
+
+        assert_eq!(hashvec.max_value_by(|a, b| a.len().cmp(&b.len())), Some((&2, &"banana")));
+        assert_eq!(hashvec.min_value_by(|a, b| a.len().cmp(&b.len())), Some((&3, &"fig")));
+
+        let empty: HashVec<u32, &str> = HashVec::new();
+        assert_eq!(empty.max_value_by(|a, b| a.cmp(b)), None);
+    }
+
+    #[test]
+    fn entries_eq_compares_in_order() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("c", 3), ("a", 1), ("b", 2)];
+        assert!(hashvec.entries_eq(&[("c", 3), ("a", 1), ("b", 2)]));
+
+        hashvec.sort_by_cached_key(|k, _| *k);
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+        assert!(!hashvec.entries_eq(&[("c", 3), ("a", 1), ("b", 2)]));
+        assert!(!hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+    }
+
+    #[test]
+    fn reserve_holds_for_both_structures() {
+        for additional in [1, 7, 8, 100, 1000, 3000] {
+            let mut hashvec: HashVec<u32, u32> = (0..5).map(|i| (i, i)).collect();
+            hashvec.reserve(additional);
+            let entries_capacity = hashvec.entries.capacity();
+            let order_capacity = hashvec.order.capacity();
+
+            for i in 0..additional as u32 {
+                hashvec.insert(i + 5, i);
+            }
+
+            // Neither structure reallocated (or rehashed) while filling the reservation
+            assert_eq!(hashvec.entries.capacity(), entries_capacity, "entries grew for {}", additional);
+            assert_eq!(hashvec.order.capacity(), order_capacity, "index map rehashed for {}", additional);
+            hashvec.assert_consistent();
+        }
+    }
+
+    #[test]
+    fn swap_keys_accepts_borrowed_keys() {
+        let mut hashvec: HashVec<String, i32> = HashVec::new();
+        hashvec.insert("a".to_string(), 1);
+        hashvec.insert("b".to_string(), 2);
+        hashvec.insert("c".to_string(), 3);
+
+        hashvec.swap_keys("a", "c");
+        assert_eq!(hashvec[0], ("c".to_string(), 3));
+        assert_eq!(hashvec[2], ("a".to_string(), 1));
+
+        // Missing keys make it a no-op
+        hashvec.swap_keys("a", "z");
+        assert_eq!(hashvec[2], ("a".to_string(), 1));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn into_mut_keeps_position() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        if let Entry::Occupied(entry) = hashvec.entry("a") {
+            *entry.into_mut() += 10;
+        }
+
+        assert!(hashvec.entries_eq(&[("a", 11), ("b", 2), ("c", 3)]));
+    }
+
+    #[test]
+    fn into_mut_pushed_moves_to_the_back() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        if let Entry::Occupied(entry) = hashvec.entry("a") {
+            *entry.into_mut_pushed() += 10;
+        }
+
+        assert!(hashvec.entries_eq(&[("b", 2), ("c", 3), ("a", 11)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn vacant_insert_appends() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1)];
+        if let Entry::Vacant(entry) = hashvec.entry("b") {
+            *entry.insert(2) += 1;
+        }
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn to_ordered_pairs_numbers_entries() {
+        let hashvec: HashVec<&str, i32> = hashvec![("b", 2), ("a", 1), ("c", 3)];
+        let triples = hashvec.to_ordered_pairs();
+
+        assert_eq!(triples, vec![(0, &"b", &2), (1, &"a", &1), (2, &"c", &3)]);
+        assert!(triples.iter().enumerate().all(|(i, (index, _, _))| i == *index));
+    }
+
+    #[test]
+    fn retain_matches_per_item_removal() {
+        let mut retained: HashVec<u32, u32> = (0..50).map(|i| (i, i)).collect();
+        let mut removed = retained.clone();
+
+        retained.retain(|_, v| v % 3 != 0);
+        for i in (0..50).filter(|i| i % 3 == 0) {
+            removed.remove_key(&i);
+        }
+
+        assert!(retained.entries_eq(&removed.entries));
+        retained.assert_consistent();
+    }
+
+    #[test]
+    fn retain_tracks_each_survivor_once() {
+        let mut hashvec: HashVec<Counted, u32> = (0..10).map(|i| (Counted(i), i)).collect();
+        let capacity = hashvec.entries.capacity();
+
+        // Rebuilding the index hashes each of the 4 survivors once, and nothing else
+        let (_, hashes) = count_hashes(|| hashvec.retain(|k, _| k.0 >= 6));
+        assert_eq!(hashes, 4);
+        assert_eq!(hashvec.entries.capacity(), capacity);
+        for (i, k) in (6..10).enumerate() {
+            assert_eq!(hashvec.index(&Counted(k)), Some(i));
+        }
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn index_entry_mutates_then_removes() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        let mut entry = hashvec.index_entry(1).unwrap();
+        assert_eq!(entry.key(), &"b");
+        *entry.get_mut() += 10;
+        assert_eq!(entry.get(), &12);
+        assert_eq!(entry.remove(), 12);
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("c", 3)]));
+        assert!(hashvec.index_entry(2).is_none());
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn concat_merges_with_last_write_wins() {
+        let first: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+        let second: HashVec<&str, i32> = hashvec![("c", 3), ("a", 10)];
+        let third: HashVec<&str, i32> = hashvec![("b", 20), ("d", 4)];
+        let merged = HashVec::concat(vec![first, second, third]);
+
+        // Overwritten keys move to the back, just like append
+        assert!(merged.entries_eq(&[("c", 3), ("a", 10), ("b", 20), ("d", 4)]));
+        merged.assert_consistent();
+    }
+
+    #[test]
+    fn neighbors_at_middle_and_ends() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.neighbors(&"b"), Some((Some((&"a", &1)), Some((&"c", &3)))));
+        assert_eq!(hashvec.neighbors(&"a"), Some((None, Some((&"b", &2)))));
+        assert_eq!(hashvec.neighbors(&"c"), Some((Some((&"b", &2)), None)));
+        assert_eq!(hashvec.neighbors(&"z"), None);
+    }
+
+    #[test]
+    fn retain_changed_reports_no_removals() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+
+        assert!(!hashvec.retain_changed(|_, _| true));
+        assert_eq!(hashvec.len(), 2);
+    }
+
+    #[test]
+    fn retain_changed_reports_removals() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert!(hashvec.retain_changed(|_, v| *v != 2));
+        assert!(hashvec.entries_eq(&[("a", 1), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn get_or_insert_default_groups_values() {
+        let mut groups: HashVec<usize, Vec<&str>> = HashVec::new();
+        for word in ["fig", "kiwi", "yam", "pear", "banana"] {
+            groups.get_or_insert_default(word.len()).push(word);
+        }
+
+        assert!(groups.entries_eq(&[(3, vec!["fig", "yam"]), (4, vec!["kiwi", "pear"]), (6, vec!["banana"])]));
+        groups.assert_consistent();
+    }
+
+    #[test]
+    fn reverse_iter_matches_reversed_entries() {
+        let hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        let reversed: Vec<(&&str, &i32)> = hashvec.reverse_iter().collect();
+        let expected: Vec<(&&str, &i32)> = hashvec.entries.iter().rev().map(|(k, v)| (k, v)).collect();
+        assert_eq!(reversed, expected);
+    }
+
+    #[test]
+    fn truncate_extract_returns_the_tail() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+
+        assert_eq!(hashvec.truncate_extract(2), vec![("c", 3), ("d", 4)]);
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+        hashvec.assert_consistent();
+
+        assert!(hashvec.truncate_extract(2).is_empty());
+        assert!(hashvec.truncate_extract(10).is_empty());
+        assert_eq!(hashvec.len(), 2);
+    }
+
+    #[test]
+    fn truncate_drops_the_tail() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.truncate(1);
+
+        assert!(hashvec.entries_eq(&[("a", 1)]));
+        assert!(!hashvec.contains_key(&"c"));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn key_set_subset_and_superset() {
+        let small: HashVec<&str, i32> = hashvec![("b", 2), ("a", 1)];
+        let large: HashVec<&str, char> = hashvec![("a", 'x'), ("b", 'y'), ("c", 'z')];
+
+        assert_eq!(small.key_set().collect::<Vec<&&str>>(), vec![&"b", &"a"]);
+        assert!(small.is_subset_of(&large));
+        assert!(!small.is_superset_of(&large));
+        assert!(large.is_superset_of(&small));
+        assert!(!large.is_subset_of(&small));
+    }
+
+    #[test]
+    fn key_set_disjoint() {
+        let left: HashVec<&str, i32> = hashvec![("a", 1)];
+        let right: HashVec<&str, i32> = hashvec![("b", 2)];
+
+        assert!(!left.is_subset_of(&right));
+        assert!(!left.is_superset_of(&right));
+        assert!(HashVec::<&str, i32>::new().is_subset_of(&right));
+    }
+
+    #[test]
+    fn or_insert_with_index_interns_keys() {
+        let mut interner: HashVec<&str, usize> = HashVec::new();
+        let mut ids = Vec::new();
+        for word in ["to", "be", "or", "not", "to", "be"] {
+            let (index, id) = interner.entry(word).or_insert_with_index(|index| index);
+            ids.push((index, *id));
+        }
+
+        assert_eq!(ids, vec![(0, 0), (1, 1), (2, 2), (3, 3), (0, 0), (1, 1)]);
+        for (k, id) in interner.iter() {
+            assert_eq!(interner.index(k), Some(*id));
+        }
+    }
+
+    #[test]
+    fn dedup_collapses_adjacent_duplicates() {
+        let mut hashvec = HashVec::from_entries(vec![("a", 1), ("a", 1), ("b", 2), ("a", 1), ("c", 3), ("c", 3)]);
+        hashvec.dedup();
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("a", 1), ("c", 3)]));
+        assert_eq!(hashvec.index(&"c"), Some(3));
+    }
+
+    #[test]
+    fn swap_indices_with_itself_is_a_no_op() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.swap_indices(1, 1);
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+        for (i, k) in ["a", "b", "c"].iter().enumerate() {
+            assert_eq!(hashvec.index(k), Some(i));
+        }
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn swap_indices_adjacent() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.swap_indices(1, 2);
+        hashvec.swap_indices(1, 0);
+
+        assert!(hashvec.entries_eq(&[("c", 3), ("a", 1), ("b", 2)]));
+        for (i, k) in ["c", "a", "b"].iter().enumerate() {
+            assert_eq!(hashvec.index(k), Some(i));
+        }
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn into_iter_indexed_yields_owned_entries() {
+        let mut hashvec: HashVec<String, String> = HashVec::new();
+        hashvec.insert("a".to_string(), "x".to_string());
+        hashvec.insert("b".to_string(), "y".to_string());
+
+        let owned: Vec<(usize, String, String)> = hashvec.into_iter_indexed().collect();
+        assert_eq!(owned, vec![
+            (0, "a".to_string(), "x".to_string()),
+            (1, "b".to_string(), "y".to_string())
+        ]);
+    }
+
+    #[test]
+    fn update_or_insert_counts() {
+        let mut counts: HashVec<&str, u32> = HashVec::new();
+        for word in ["a", "b", "a", "a", "c", "b"] {
+            counts.update_or_insert(word, |v| *v += 1, 1);
+        }
+
+        assert!(counts.entries_eq(&[("a", 3), ("b", 2), ("c", 1)]));
+        counts.assert_consistent();
+    }
+
+    #[test]
+    fn update_or_insert_hashes_once() {
+        let mut hashvec: HashVec<Counted, u32> = (0..3).map(|i| (Counted(i), i)).collect();
+        let order = hashvec.order.clone();
+
+        // Updating an existing key hashes it once and leaves the index map untouched
+        let (_, hashes) = count_hashes(|| hashvec.update_or_insert(Counted(0), |v| *v += 10, 0));
+        assert_eq!(hashes, 1);
+        assert_eq!(hashvec.order, order);
+
+        // Inserting a new key hashes it once too
+        let (_, hashes) = count_hashes(|| hashvec.update_or_insert(Counted(3), |_| unreachable!(), 3));
+        assert_eq!(hashes, 1);
+
+        assert!(hashvec.entries_eq(&[(Counted(0), 10), (Counted(1), 1), (Counted(2), 2), (Counted(3), 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn rename_matching_prefixes_selected_keys() {
+        let mut hashvec: HashVec<String, i32> = HashVec::new();
+        for (k, v) in [("apple", 1), ("banana", 2), ("avocado", 3)] {
+            hashvec.insert(k.to_string(), v);
+        }
+        hashvec.rename_matching(|k| k.starts_with('a'), |k| format!("fruit.{}", k));
+
+        assert_eq!(hashvec.index(&"fruit.apple".to_string()), Some(0));
+        assert_eq!(hashvec.index(&"banana".to_string()), Some(1));
+        assert_eq!(hashvec.get(&"fruit.avocado".to_string()), Some(&3));
+        assert!(!hashvec.contains_key(&"apple".to_string()));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn rename_matching_collapses_collisions() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+        hashvec.rename_matching(|k| *k == "c", |_| "a");
+
+        // The first entry with each key wins
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn checked_insert_at_valid_and_out_of_range() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+
+        assert_eq!(hashvec.checked_insert_at(1, "c", 3), Ok(None));
+        assert_eq!(hashvec.checked_insert_at(0, "b", 20), Ok(Some(2)));
+        assert!(hashvec.entries_eq(&[("b", 20), ("a", 1), ("c", 3)]));
+
+        assert_eq!(hashvec.checked_insert_at(4, "d", 4), Err(("d", 4)));
+        assert_eq!(hashvec.checked_insert_at(3, "a", 10), Err(("a", 10)));
+        assert!(hashvec.entries_eq(&[("b", 20), ("a", 1), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 is out of bounds for a hashvec of length 2")]
+    fn insert_at_out_of_range_panics() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+        hashvec.insert_at(3, "c", 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot insert a new key into a full hashvec")]
+    fn insert_at_full_panics() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+        hashvec.set_max_len(Some(2));
+        hashvec.insert_at(0, "c", 3);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_values_mut_matches_serial_pass() {
+        let mut parallel: HashVec<usize, u64> = (0..1000).map(|i| (i, i as u64)).collect();
+        let mut serial = parallel.clone();
+
+        parallel.par_values_mut().for_each(|v| *v *= 2);
+        serial.values_mut().for_each(|v| *v *= 2);
+
+        assert_eq!(parallel.entries, serial.entries);
+        parallel.assert_consistent();
+    }
+
+    #[test]
+    fn sort_by_cached_key_projects_once_per_entry() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("c", 3), ("a", 1), ("d", 1), ("b", 2)];
+        let calls = std::cell::Cell::new(0);
+        hashvec.sort_by_cached_key(|_, v| {
+            calls.set(calls.get() + 1);
+            *v
+        });
+
+        assert_eq!(calls.get(), 4);
+        // "a" and "d" tie, so they keep their original relative order
+        assert!(hashvec.entries_eq(&[("a", 1), ("d", 1), ("b", 2), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn remove_if_removes_matching_value() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2), ("c", 3)];
+
+        assert_eq!(hashvec.remove_if(&"b", |v| *v == 2), Some(2));
+        assert!(hashvec.entries_eq(&[("a", 1), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn remove_if_keeps_rejected_and_absent_keys() {
+        let mut hashvec: HashVec<&str, i32> = hashvec![("a", 1), ("b", 2)];
+
+        assert_eq!(hashvec.remove_if(&"a", |v| *v > 5), None);
+        assert_eq!(hashvec.remove_if(&"z", |_| true), None);
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2)]));
+    }
+
+    #[test]
+    fn with_capacity_holds_capacity_inserts_without_growing() {
+        for n in [1, 7, 64, 1000] {
+            let mut hashvec: HashVec<usize, usize> = HashVec::with_capacity(n);
+            let (callback, events) = recording_callback();
+            hashvec.set_grow_callback(callback);
+            let entries_capacity = hashvec.entries.capacity();
+            let order_capacity = hashvec.order.capacity();
+
+            for i in 0..n {
+                hashvec.insert(i, i);
+            }
+
+            assert_eq!(hashvec.entries.capacity(), entries_capacity);
+            assert_eq!(hashvec.order.capacity(), order_capacity);
+            assert!(events.lock().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn from_iter_sorted_unique_keys() {
+        let hashvec: HashVec<&str, i32> = HashVec::from_iter_sorted(vec![("a", 1), ("b", 2), ("c", 3)]);
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 2), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn from_iter_sorted_overwrites_repeats_in_place() {
+        let hashvec: HashVec<&str, i32> = HashVec::from_iter_sorted(vec![("a", 1), ("b", 2), ("b", 20), ("c", 3)]);
+
+        assert!(hashvec.entries_eq(&[("a", 1), ("b", 20), ("c", 3)]));
+        hashvec.assert_consistent();
+    }
+
+    #[test]
+    fn from_iter_sorted_hashes_once_and_grows_in_lockstep() {
+        // A filtered source has a size hint of zero, so the hashvec has to grow as it goes
+        let source = (0..1000).map(|i| (Counted(i), i)).filter(|(k, _)| k.0 % 3 != 0);
+        let (hashvec, hashes) = count_hashes(|| HashVec::from_iter_sorted(source));
+
+        assert_eq!(hashes, hashvec.len());
+        assert!(hashvec.order.capacity() >= hashvec.entries.capacity());
+        hashvec.assert_consistent();
+    }
+}